@@ -0,0 +1,174 @@
+//! How the break overlay looks: `overlayMode`, `overlayTheme`, `overlayBackgroundImage`
+//! and `overlayBlur`.
+//!
+//! The overlay can't read arbitrary files itself, so a background image is loaded here and
+//! handed over as a data URL. A missing or unusable image is logged and the overlay keeps
+//! its built-in backdrop.
+//!
+//! Blurring the desktop behind the overlay needs OS window effects. Only Windows 11 gets
+//! them: macOS would need the private transparency API the app doesn't enable, and Linux
+//! has none. Elsewhere `overlayBlur` is ignored and the usual backdrop is drawn.
+
+use std::path::Path;
+
+use base64::Engine;
+use tauri::{AppHandle, LogicalPosition, LogicalSize, Monitor, WebviewWindow};
+
+/// Largest background image the overlay is handed, in bytes.
+const MAX_BACKGROUND_BYTES: u64 = 20 * 1024 * 1024;
+/// Size of the corner toast, in logical pixels.
+const TOAST_SIZE: LogicalSize<f64> = LogicalSize::new(380.0, 220.0);
+/// Gap between the corner toast and the edges of the screen.
+const TOAST_MARGIN: f64 = 24.0;
+
+/// First Windows 11 build; older ones render the acrylic effect too slowly to rely on.
+#[cfg(target_os = "windows")]
+const WINDOWS_11_BUILD: u32 = 22000;
+
+/// `overlayMode`: how much of the screen a break takes over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverlayMode {
+    Fullscreen,
+    /// A card of `overlayWidth` by `overlayHeight`, centred on the monitor.
+    Windowed,
+    /// A small card in the bottom-right corner that doesn't take focus.
+    CornerToast,
+}
+
+pub fn overlay_mode(app: &AppHandle) -> OverlayMode {
+    match crate::get_string_setting(app, "overlayMode", "fullscreen").as_str() {
+        "windowed" => OverlayMode::Windowed,
+        "corner-toast" => OverlayMode::CornerToast,
+        _ => OverlayMode::Fullscreen,
+    }
+}
+
+/// The size of an overlay in `mode` on `monitor`, or on an unknown screen for `None`.
+pub fn overlay_size(app: &AppHandle, mode: OverlayMode, monitor: Option<&Monitor>) -> LogicalSize<f64> {
+    let screen = monitor.map(|monitor| monitor.size().to_logical::<f64>(monitor.scale_factor()));
+    let size = match mode {
+        OverlayMode::Fullscreen => return screen.unwrap_or(LogicalSize::new(800.0, 600.0)),
+        OverlayMode::Windowed => LogicalSize::new(
+            crate::get_u64_setting(app, "overlayWidth", 800) as f64,
+            crate::get_u64_setting(app, "overlayHeight", 600) as f64,
+        ),
+        OverlayMode::CornerToast => TOAST_SIZE,
+    };
+    match screen {
+        Some(screen) => LogicalSize::new(size.width.min(screen.width), size.height.min(screen.height)),
+        None => size,
+    }
+}
+
+/// Where an overlay of `size` in `mode` goes on `monitor`.
+pub fn overlay_position(mode: OverlayMode, monitor: &Monitor, size: LogicalSize<f64>) -> LogicalPosition<f64> {
+    let scale = monitor.scale_factor();
+    let origin = monitor.position().to_logical::<f64>(scale);
+    let screen = monitor.size().to_logical::<f64>(scale);
+    match mode {
+        OverlayMode::Fullscreen => origin,
+        OverlayMode::Windowed => LogicalPosition::new(
+            origin.x + (screen.width - size.width) / 2.0,
+            origin.y + (screen.height - size.height) / 2.0,
+        ),
+        OverlayMode::CornerToast => LogicalPosition::new(
+            origin.x + screen.width - size.width - TOAST_MARGIN,
+            origin.y + screen.height - size.height - TOAST_MARGIN,
+        ),
+    }
+}
+
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+/// `overlayTheme`, with "auto" resolved from the OS appearance as seen by `window`.
+pub fn overlay_theme(app: &AppHandle, window: &WebviewWindow) -> Theme {
+    match crate::get_string_setting(app, "overlayTheme", "dark").as_str() {
+        "light" => Theme::Light,
+        "auto" => match window.theme() {
+            Ok(tauri::Theme::Light) => Theme::Light,
+            Ok(_) => Theme::Dark,
+            Err(e) => {
+                log::warn!("⚠️ Couldn't read the system theme, using dark: {}", e);
+                Theme::Dark
+            }
+        },
+        _ => Theme::Dark,
+    }
+}
+
+fn image_mime_type(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        "bmp" => Some("image/bmp"),
+        "svg" => Some("image/svg+xml"),
+        _ => None,
+    }
+}
+
+fn load_image(path: &Path) -> Result<String, String> {
+    let mime = image_mime_type(path).ok_or("not a supported image type")?;
+    let metadata = std::fs::metadata(path).map_err(|e| e.to_string())?;
+    if !metadata.is_file() {
+        return Err("not a file".to_string());
+    }
+    if metadata.len() > MAX_BACKGROUND_BYTES {
+        return Err(format!("larger than {} MB", MAX_BACKGROUND_BYTES / 1024 / 1024));
+    }
+    let bytes = std::fs::read(path).map_err(|e| e.to_string())?;
+    Ok(format!(
+        "data:{};base64,{}",
+        mime,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+/// `overlayBackgroundImage` as a data URL, or `None` for the built-in backdrop.
+pub fn overlay_background_image(app: &AppHandle) -> Option<String> {
+    let path = crate::get_string_setting(app, "overlayBackgroundImage", "");
+    let path = path.trim();
+    if path.is_empty() {
+        return None;
+    }
+    match load_image(Path::new(path)) {
+        Ok(url) => Some(url),
+        Err(e) => {
+            log::warn!("⚠️ Can't use background image '{}', using the default: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Whether the overlay window can blur what is behind it on this system.
+#[cfg(target_os = "windows")]
+pub fn blur_supported() -> bool {
+    use windows_sys::Wdk::System::SystemServices::RtlGetVersion;
+    use windows_sys::Win32::System::SystemInformation::OSVERSIONINFOW;
+
+    let mut info: OSVERSIONINFOW = unsafe { std::mem::zeroed() };
+    info.dwOSVersionInfoSize = std::mem::size_of::<OSVERSIONINFOW>() as u32;
+    // Unlike GetVersionEx, this reports the real version whatever the app manifest says.
+    if unsafe { RtlGetVersion(&mut info) } != 0 {
+        return false;
+    }
+    info.dwBuildNumber >= WINDOWS_11_BUILD
+}
+
+#[cfg(not(target_os = "windows"))]
+pub fn blur_supported() -> bool {
+    false
+}
+
+/// `overlayBlur`, where the system can do it.
+pub fn overlay_blur(app: &AppHandle) -> bool {
+    crate::get_bool_setting(app, "overlayBlur", false) && blur_supported()
+}
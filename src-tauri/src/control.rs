@@ -0,0 +1,228 @@
+//! Optional local control endpoint for scripts: a Unix socket in a private directory under
+//! the app data directory, or the `\\.\pipe\nudge` named pipe on Windows.
+//!
+//! Only the user the app runs as can connect: the socket's directory is `0700` before the
+//! socket exists, and the pipe is created with a DACL granting access to that user's SID
+//! alone. It is still off unless `enableLocalControlSocket` is set, since any program
+//! running as the user can talk to it. Each line is one request, either `{"command": "pause"}` or just
+//! `pause`, and is answered with one line of JSON: `{"ok": true, "result": ...}` or
+//! `{"ok": false, "error": "..."}`. The commands are `pause`, `resume`, `break-now` and
+//! `status`.
+
+use serde_json::{json, Value};
+use tauri::AppHandle;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+#[cfg(unix)]
+const SOCKET_DIR: &str = "control";
+#[cfg(unix)]
+const SOCKET_FILE: &str = "nudge.sock";
+#[cfg(windows)]
+const PIPE_NAME: &str = r"\\.\pipe\nudge";
+
+/// Starts listening if `enableLocalControlSocket` is on. Called once from `setup`, so
+/// turning the setting on or off takes effect on the next launch.
+pub fn start(app: &AppHandle) {
+    if !crate::get_bool_setting(app, "enableLocalControlSocket", false) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = serve(app).await {
+            log::error!("❌ Local control socket stopped: {}", e);
+        }
+    });
+}
+
+#[cfg(unix)]
+async fn serve(app: AppHandle) -> Result<(), String> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+    use tauri::Manager;
+
+    // The socket is reachable the moment it is bound, so it goes in a directory nobody
+    // else can enter rather than being locked down afterwards.
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join(SOCKET_DIR);
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(&dir)
+        .map_err(|e| format!("{}: {}", dir.display(), e))?;
+    // Created earlier with looser permissions, or by someone else: fails for the latter.
+    std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o700))
+        .map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let path = dir.join(SOCKET_FILE);
+    // Left behind if the app didn't exit cleanly; binding fails while it exists.
+    let _ = std::fs::remove_file(&path);
+    let listener = tokio::net::UnixListener::bind(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).map_err(|e| e.to_string())?;
+    log::info!("🔌 Local control socket listening at {}", path.display());
+
+    loop {
+        let (stream, _) = listener.accept().await.map_err(|e| e.to_string())?;
+        tauri::async_runtime::spawn(handle_connection(app.clone(), stream));
+    }
+}
+
+#[cfg(windows)]
+async fn serve(app: AppHandle) -> Result<(), String> {
+    let mut security = PipeSecurity::current_user_only()?;
+    let mut server = security.create(true).map_err(|e| format!("{}: {}", PIPE_NAME, e))?;
+    log::info!("🔌 Local control pipe listening at {}", PIPE_NAME);
+
+    loop {
+        server.connect().await.map_err(|e| e.to_string())?;
+        // A fresh instance takes over listening while this one serves its client.
+        let connected = server;
+        server = security.create(false).map_err(|e| e.to_string())?;
+        tauri::async_runtime::spawn(handle_connection(app.clone(), connected));
+    }
+}
+
+/// Security attributes for pipe instances that only the current user may open.
+#[cfg(windows)]
+struct PipeSecurity {
+    attributes: windows_sys::Win32::Security::SECURITY_ATTRIBUTES,
+}
+
+// The descriptor is owned by this value and never changed after it is built.
+#[cfg(windows)]
+unsafe impl Send for PipeSecurity {}
+
+#[cfg(windows)]
+impl PipeSecurity {
+    fn current_user_only() -> Result<Self, String> {
+        use windows_sys::Win32::Security::Authorization::{
+            ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+        };
+        use windows_sys::Win32::Security::SECURITY_ATTRIBUTES;
+
+        // Protected DACL with a single entry: full access for the user's SID.
+        let sddl: Vec<u16> = format!("D:P(A;;GA;;;{})", current_user_sid()?)
+            .encode_utf16()
+            .chain(Some(0))
+            .collect();
+        let mut descriptor = std::ptr::null_mut();
+        let converted = unsafe {
+            ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                sddl.as_ptr(),
+                SDDL_REVISION_1,
+                &mut descriptor,
+                std::ptr::null_mut(),
+            )
+        };
+        if converted == 0 {
+            return Err(format!("Can't build the pipe's security descriptor: {}", std::io::Error::last_os_error()));
+        }
+        Ok(PipeSecurity {
+            attributes: SECURITY_ATTRIBUTES {
+                nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+                lpSecurityDescriptor: descriptor,
+                bInheritHandle: 0,
+            },
+        })
+    }
+
+    fn create(&mut self, first: bool) -> std::io::Result<tokio::net::windows::named_pipe::NamedPipeServer> {
+        let attributes: *mut windows_sys::Win32::Security::SECURITY_ATTRIBUTES = &mut self.attributes;
+        unsafe {
+            tokio::net::windows::named_pipe::ServerOptions::new()
+                .first_pipe_instance(first)
+                .reject_remote_clients(true)
+                .create_with_security_attributes_raw(PIPE_NAME, attributes.cast())
+        }
+    }
+}
+
+#[cfg(windows)]
+impl Drop for PipeSecurity {
+    fn drop(&mut self) {
+        unsafe { windows_sys::Win32::Foundation::LocalFree(self.attributes.lpSecurityDescriptor) };
+    }
+}
+
+/// The SID of the user the app runs as, in its `S-1-5-...` string form.
+#[cfg(windows)]
+fn current_user_sid() -> Result<String, String> {
+    use windows_sys::Win32::Foundation::{CloseHandle, LocalFree};
+    use windows_sys::Win32::Security::Authorization::ConvertSidToStringSidW;
+    use windows_sys::Win32::Security::{GetTokenInformation, TokenUser, TOKEN_QUERY, TOKEN_USER};
+    use windows_sys::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    let last_error = || std::io::Error::last_os_error();
+    let mut token = std::ptr::null_mut();
+    if unsafe { OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token) } == 0 {
+        return Err(format!("Can't open the process token: {}", last_error()));
+    }
+    let mut len = 0;
+    unsafe { GetTokenInformation(token, TokenUser, std::ptr::null_mut(), 0, &mut len) };
+    // u64s keep the TOKEN_USER at the start suitably aligned.
+    let mut buffer = vec![0u64; (len as usize).div_ceil(8)];
+    let read = unsafe { GetTokenInformation(token, TokenUser, buffer.as_mut_ptr().cast(), len, &mut len) };
+    unsafe { CloseHandle(token) };
+    if read == 0 {
+        return Err(format!("Can't read the process user: {}", last_error()));
+    }
+    let user = unsafe { &*buffer.as_ptr().cast::<TOKEN_USER>() };
+
+    let mut sid = std::ptr::null_mut();
+    if unsafe { ConvertSidToStringSidW(user.User.Sid, &mut sid) } == 0 {
+        return Err(format!("Can't format the user's SID: {}", last_error()));
+    }
+    let len = (0..).take_while(|&i| unsafe { *sid.add(i) } != 0).count();
+    let text = String::from_utf16_lossy(unsafe { std::slice::from_raw_parts(sid, len) });
+    unsafe { LocalFree(sid.cast()) };
+    Ok(text)
+}
+
+#[cfg(not(any(unix, windows)))]
+async fn serve(_app: AppHandle) -> Result<(), String> {
+    Err("Not supported on this platform".to_string())
+}
+
+async fn handle_connection<S>(app: AppHandle, stream: S)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match handle_request(&app, &line).await {
+            Ok(result) => json!({ "ok": true, "result": result }),
+            Err(e) => json!({ "ok": false, "error": e }),
+        };
+        let reply = format!("{}\n", response);
+        if writer.write_all(reply.as_bytes()).await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Takes the command out of a request line, which is either a bare word or a JSON object
+/// with a `command` field.
+fn parse_command(line: &str) -> Result<String, String> {
+    let line = line.trim();
+    if !line.starts_with('{') {
+        return Ok(line.to_string());
+    }
+    let request: Value = serde_json::from_str(line).map_err(|e| format!("Malformed request: {}", e))?;
+    request
+        .get("command")
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .ok_or_else(|| "Request has no \"command\"".to_string())
+}
+
+async fn handle_request(app: &AppHandle, line: &str) -> Result<Value, String> {
+    let command = parse_command(line)?;
+    log::info!("🔌 Local control command: {}", command);
+    match command.as_str() {
+        "pause" => crate::pause_timer(app.clone(), None).map(|()| Value::Null),
+        "resume" => crate::resume_timer(app.clone()).map(|()| Value::Null),
+        "break-now" => crate::trigger_overlay(app.clone(), None).await.map(|()| Value::Null),
+        "status" => serde_json::to_value(crate::get_timer_status(app.clone())).map_err(|e| e.to_string()),
+        other => Err(format!("Unknown command '{}'", other)),
+    }
+}
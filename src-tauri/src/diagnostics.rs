@@ -0,0 +1,54 @@
+//! A read-only snapshot of the app's configuration and environment for support requests.
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_autostart::ManagerExt;
+use tauri_plugin_store::StoreExt;
+
+use crate::SETTINGS_STORE;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiagnosticReport {
+    app_version: String,
+    os: &'static str,
+    arch: &'static str,
+    /// Every user setting as stored, the same set `export_settings` writes out.
+    settings: Map<String, Value>,
+    /// Whether the OS has the app registered to launch at login; `None` if that can't be read.
+    autostart_registered: Option<bool>,
+    idle_detection: bool,
+    /// Whether the overlay can blur the desktop (`overlayBlur`) on this system.
+    blur_supported: bool,
+    tray_available: bool,
+    monitor_count: usize,
+    /// Local ISO time the next break is due, if one is scheduled.
+    next_break_at: Option<String>,
+}
+
+/// Gathers the report. Only reads: nothing is stored, scheduled or picked.
+#[tauri::command]
+pub fn run_diagnostics(app: AppHandle) -> Result<DiagnosticReport, String> {
+    log::debug!("🩺 run_diagnostics command received!");
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    let autostart_registered = match app.autolaunch().is_enabled() {
+        Ok(enabled) => Some(enabled),
+        Err(e) => {
+            log::warn!("⚠️ Couldn't read the autostart registration: {}", e);
+            None
+        }
+    };
+    Ok(DiagnosticReport {
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        settings: crate::settings::user_settings(&store),
+        autostart_registered,
+        idle_detection: crate::idle::SUPPORTED,
+        blur_supported: crate::appearance::blur_supported(),
+        tray_available: app.try_state::<crate::TrayState>().is_some(),
+        monitor_count: app.available_monitors().map(|monitors| monitors.len()).unwrap_or(0),
+        next_break_at: crate::schedule::upcoming_breaks(app.clone(), 1).into_iter().next(),
+    })
+}
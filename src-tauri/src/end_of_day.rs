@@ -0,0 +1,119 @@
+//! The wind-down break at the end of the workday (`endOfDayBreak`): one longer break at
+//! `workEndTime`, with its own text and length, prompting to wrap up.
+//!
+//! It fires at most once a day, on `workDays`, and only if the app is running within
+//! `LATE_LIMIT` of the end time, so starting the computer in the evening doesn't greet
+//! anyone with it. The day it fired on is kept in the store; with `endOfDaySuppressBreaks`
+//! the regular breaks stop for the rest of that day.
+
+use std::time::Duration;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::reminders::ReminderType;
+use crate::schedule::Clock;
+use crate::{time_window, BreakKind, SystemClock, TimerState, SETTINGS_STORE};
+
+/// How often the end of the day is checked for. Polling, rather than one long sleep,
+/// keeps it on time across suspends and picks up settings changes by itself.
+const CHECK_PERIOD: Duration = Duration::from_secs(30);
+/// How long after `workEndTime` the break may still fire, if something held it up.
+const LATE_LIMIT: chrono::Duration = chrono::Duration::minutes(30);
+/// Internal key holding the date the break last fired on.
+const LAST_FIRED_KEY: &str = "endOfDayBreakDate";
+
+/// Whether the end-of-day break is due at `now`: enabled, on a work day, within
+/// `LATE_LIMIT` of `workEndTime`, and not already shown today.
+fn due_at(app: &AppHandle, now: NaiveDateTime) -> bool {
+    if !crate::get_bool_setting(app, "endOfDayBreak", false) {
+        return false;
+    }
+    let settings = crate::weekday::WeekdaySettings::load(app);
+    let Ok(end) = time_window::parse_hhmm(settings.string_on("workEndTime", now.weekday()).unwrap_or_default()) else {
+        return false;
+    };
+    let days: Vec<String> = crate::get_setting(app, "workDays")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    let work_day = days.is_empty()
+        || days
+            .iter()
+            .filter_map(|day| time_window::parse_weekday(day).ok())
+            .any(|day| day == now.weekday());
+    let end = now.date().and_time(end);
+    work_day && now >= end && now < end + LATE_LIMIT && last_fired(app) != Some(now.date())
+}
+
+/// The day the end-of-day break last fired on.
+pub fn last_fired(app: &AppHandle) -> Option<NaiveDate> {
+    crate::get_string_setting(app, LAST_FIRED_KEY, "").parse().ok()
+}
+
+fn set_last_fired(app: &AppHandle, date: NaiveDate) {
+    let store = match app.store(SETTINGS_STORE) {
+        Ok(store) => store,
+        Err(e) => {
+            log::error!("❌ Failed to open the settings store: {}", e);
+            return;
+        }
+    };
+    store.set(LAST_FIRED_KEY, serde_json::json!(date.to_string()));
+    if let Err(e) = store.save() {
+        log::error!("❌ Failed to save {}: {}", LAST_FIRED_KEY, e);
+    }
+}
+
+/// The day regular breaks are suppressed for, if `endOfDaySuppressBreaks` is on.
+pub fn suppressed_day(app: &AppHandle) -> Option<NaiveDate> {
+    if !crate::get_bool_setting(app, "endOfDaySuppressBreaks", true) {
+        return None;
+    }
+    last_fired(app)
+}
+
+/// What the overlay shows: `endOfDayBreakMessage`, or the built-in text.
+pub fn reminder(app: &AppHandle) -> ReminderType {
+    let message = crate::get_string_setting(app, "endOfDayBreakMessage", "");
+    ReminderType {
+        name: crate::locale::text(app, "break.endOfDay.title"),
+        icon: "🌇".to_string(),
+        message: if message.trim().is_empty() {
+            crate::locale::text(app, "break.endOfDay.message")
+        } else {
+            message
+        },
+        weight: 1,
+    }
+}
+
+/// Watches for the end of the workday for as long as the app runs.
+pub fn start(app: &AppHandle) {
+    tauri::async_runtime::spawn({
+        let app = app.clone();
+        async move {
+            loop {
+                tokio::time::sleep(CHECK_PERIOD).await;
+                let now = SystemClock.local();
+                if !due_at(&app, now) {
+                    continue;
+                }
+                let (paused, focusing, busy) = {
+                    let timer_state = app.state::<TimerState>();
+                    let timer = timer_state.0.lock().unwrap();
+                    (timer.paused, timer.focus_until.is_some(), timer.break_active && !timer.preview)
+                };
+                // Tried again on the next check, until `LATE_LIMIT` runs out.
+                if paused || focusing || busy {
+                    continue;
+                }
+                crate::close_preview(&app);
+                log::info!("🌇 Workday over, showing the end-of-day break");
+                if crate::open_overlays(&app, BreakKind::EndOfDay, false, None).await {
+                    set_last_fired(&app, now.date());
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,112 @@
+//! In-process broadcast of the break lifecycle, so features can react to it without the
+//! timer calling each of them.
+//!
+//! Whoever causes a lifecycle step publishes it with `publish`; reactors (stats, webhook,
+//! tray) each run a `subscribe` loop started at setup. Adding another reactor means adding
+//! another loop, not touching the timer. Events are only in memory: a reactor that falls
+//! more than `CAPACITY` events behind loses the oldest and logs it.
+
+use std::future::Future;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast;
+
+use crate::BreakKind;
+
+/// Events a slow subscriber may fall behind by before it starts missing some.
+const CAPACITY: usize = 64;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    /// The regular schedule's break came due; whether it shows depends on the rules.
+    BreakDue,
+    /// A real break is on screen. Previews and replays aren't published.
+    BreakShown { kind: BreakKind, duration_seconds: u64 },
+    /// A break ran its course. `timer` names the extra timer it came from, if any.
+    BreakEnded { kind: BreakKind, timer: Option<String>, elapsed: Duration },
+    /// A break was ended early.
+    Skipped { kind: BreakKind, timer: Option<String>, elapsed: Duration },
+    Paused,
+    Resumed,
+}
+
+/// The sending half of the bus, in managed state.
+pub struct EventBus(broadcast::Sender<LifecycleEvent>);
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus(broadcast::channel(CAPACITY).0)
+    }
+}
+
+impl EventBus {
+    fn send(&self, event: LifecycleEvent) {
+        log::debug!("📣 Lifecycle event: {:?}", event);
+        let _ = self.0.send(event);
+    }
+
+    fn receiver(&self) -> broadcast::Receiver<LifecycleEvent> {
+        self.0.subscribe()
+    }
+}
+
+/// Sends `event` to every subscriber. Having none is fine.
+pub fn publish(app: &AppHandle, event: LifecycleEvent) {
+    if let Some(bus) = app.try_state::<EventBus>() {
+        bus.send(event);
+    }
+}
+
+/// Runs `react` for every event published from now on, in order, for as long as the app runs.
+pub fn subscribe<F, Fut>(app: &AppHandle, name: &'static str, react: F)
+where
+    F: Fn(AppHandle, LifecycleEvent) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    let mut receiver = app.state::<EventBus>().receiver();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => react(app.clone(), event).await,
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    log::warn!("⚠️ The {} reactor fell behind and missed {} events", name, missed);
+                }
+                Err(broadcast::error::RecvError::Closed) => return,
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn a_subscriber_sees_a_break_due_shown_and_ended_in_order() {
+        let bus = EventBus::default();
+        let mut receiver = bus.receiver();
+        let elapsed = Duration::from_secs(20);
+        let published = [
+            LifecycleEvent::BreakDue,
+            LifecycleEvent::BreakShown { kind: BreakKind::Micro, duration_seconds: 20 },
+            LifecycleEvent::BreakEnded { kind: BreakKind::Micro, timer: None, elapsed },
+        ];
+        for event in published.clone() {
+            bus.send(event);
+        }
+        for event in published {
+            assert_eq!(receiver.recv().await.unwrap(), event);
+        }
+    }
+
+    #[tokio::test]
+    async fn a_subscriber_only_sees_events_published_after_it_joined() {
+        let bus = EventBus::default();
+        bus.send(LifecycleEvent::Paused);
+        let mut receiver = bus.receiver();
+        bus.send(LifecycleEvent::Resumed);
+        assert_eq!(receiver.recv().await.unwrap(), LifecycleEvent::Resumed);
+    }
+}
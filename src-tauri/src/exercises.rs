@@ -0,0 +1,64 @@
+//! Stretch and exercise suggestions for long breaks.
+//!
+//! The list ships as the `resources/exercises.json` bundle resource. An `exercises.json` in
+//! the app data directory replaces it, so users can bring their own routine.
+
+use std::path::Path;
+
+use tauri::path::BaseDirectory;
+use tauri::{AppHandle, Manager};
+
+use crate::TimerState;
+
+const BUNDLED_EXERCISES: &str = "resources/exercises.json";
+const EXERCISES_OVERRIDE: &str = "exercises.json";
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Exercise {
+    title: String,
+    description: String,
+    duration_seconds: u64,
+}
+
+fn read_exercises(path: &Path) -> Result<Vec<Exercise>, String> {
+    let json = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_str(&json).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Loads the user's override list if there is one, otherwise the bundled list. An override
+/// that can't be read is logged and skipped rather than leaving the break without guidance.
+fn load_exercises(app: &AppHandle) -> Result<Vec<Exercise>, String> {
+    if let Ok(path) = app.path().resolve(EXERCISES_OVERRIDE, BaseDirectory::AppData) {
+        if path.exists() {
+            match read_exercises(&path) {
+                Ok(exercises) if !exercises.is_empty() => return Ok(exercises),
+                Ok(_) => log::warn!("⚠️ {} is empty, using the bundled exercises", path.display()),
+                Err(e) => log::warn!("⚠️ Ignoring exercise override: {}", e),
+            }
+        }
+    }
+
+    let path = app
+        .path()
+        .resolve(BUNDLED_EXERCISES, BaseDirectory::Resource)
+        .map_err(|e| e.to_string())?;
+    read_exercises(&path)
+}
+
+/// Picks the exercise for the current break. Like the break message, it is picked once per
+/// break and shared by the overlays on every monitor.
+#[tauri::command]
+pub fn get_random_exercise(app: AppHandle) -> Result<Exercise, String> {
+    log::debug!("🤸 get_random_exercise command received!");
+    if let Some(exercise) = &app.state::<TimerState>().0.lock().unwrap().break_exercise {
+        return Ok(exercise.clone());
+    }
+    let exercises = load_exercises(&app)?;
+    if exercises.is_empty() {
+        return Err("No exercises available".to_string());
+    }
+    let exercise = exercises[crate::reminders::weighted_index(&vec![1; exercises.len()])].clone();
+    app.state::<TimerState>().0.lock().unwrap().break_exercise = Some(exercise.clone());
+    Ok(exercise)
+}
@@ -0,0 +1,138 @@
+//! A log of every break event, one entry each, kept in `history.json` for export as CSV.
+//!
+//! `stats.json` only holds running totals; this keeps the raw events behind them. Entries
+//! older than `historyRetentionDays` are dropped at launch so the file doesn't grow forever.
+
+use std::time::Duration;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::stats::BreakOutcome;
+
+const HISTORY_STORE: &str = "history.json";
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HistoryEntry {
+    /// Local time of the event, RFC 3339.
+    timestamp: String,
+    kind: String,
+    outcome: String,
+    duration_seconds: u64,
+}
+
+fn load_entries<R: tauri::Runtime>(store: &tauri_plugin_store::Store<R>) -> Vec<HistoryEntry> {
+    store
+        .get("entries")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Adds a break event to the history.
+pub fn append(app: &AppHandle, kind: &str, outcome: BreakOutcome, duration: Duration) -> Result<(), String> {
+    let store = app.store(HISTORY_STORE).map_err(|e| e.to_string())?;
+    let mut entries = load_entries(&store);
+    entries.push(HistoryEntry {
+        timestamp: Local::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+        kind: kind.to_string(),
+        outcome: outcome.as_str().to_string(),
+        duration_seconds: duration.as_secs(),
+    });
+    store.set("entries", json!(entries));
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Drops entries older than `historyRetentionDays`; 0 keeps everything.
+pub fn prune(app: &AppHandle) -> Result<(), String> {
+    let days = crate::get_u64_setting(app, "historyRetentionDays", 90);
+    if days == 0 {
+        return Ok(());
+    }
+    let store = app.store(HISTORY_STORE).map_err(|e| e.to_string())?;
+    let mut entries = load_entries(&store);
+    let cutoff = Local::now() - chrono::Duration::days(days as i64);
+    let before = entries.len();
+    // Entries that can't be dated are kept rather than lost.
+    entries.retain(|entry| {
+        chrono::DateTime::parse_from_rfc3339(&entry.timestamp).map_or(true, |at| at >= cutoff)
+    });
+    if entries.len() == before {
+        return Ok(());
+    }
+    log::info!("🧹 Pruned {} break history entries older than {} days", before - entries.len(), days);
+    store.set("entries", json!(entries));
+    store.save().map_err(|e| e.to_string())
+}
+
+/// The whole break history as CSV, oldest first, with a header row.
+#[tauri::command]
+pub fn export_history_csv(app: AppHandle) -> Result<String, String> {
+    log::debug!("📤 export_history_csv command received!");
+    let store = app.store(HISTORY_STORE).map_err(|e| e.to_string())?;
+    Ok(to_csv(&load_entries(&store)))
+}
+
+fn to_csv(entries: &[HistoryEntry]) -> String {
+    let mut csv = String::from("timestamp,kind,outcome,duration_seconds\n");
+    for entry in entries {
+        csv.push_str(&format!(
+            "{},{},{},{}\n",
+            quote(&entry.timestamp),
+            quote(&entry.kind),
+            quote(&entry.outcome),
+            entry.duration_seconds
+        ));
+    }
+    csv
+}
+
+/// A text field quoted for CSV. A timer's name is the kind of its breaks and may hold
+/// commas, quotes or line breaks.
+fn quote(field: &str) -> String {
+    format!("\"{}\"", field.replace('"', "\"\""))
+}
+
+/// Deletes the break history. The totals in the statistics are left alone.
+#[tauri::command]
+pub fn clear_history(app: AppHandle) -> Result<(), String> {
+    log::debug!("🗑️ clear_history command received!");
+    let store = app.store(HISTORY_STORE).map_err(|e| e.to_string())?;
+    store.set("entries", json!([]));
+    store.save().map_err(|e| e.to_string())?;
+    log::info!("🗑️ Break history cleared");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(kind: &str) -> HistoryEntry {
+        HistoryEntry {
+            timestamp: "2026-10-16T10:00:00+02:00".to_string(),
+            kind: kind.to_string(),
+            outcome: "completed".to_string(),
+            duration_seconds: 20,
+        }
+    }
+
+    #[test]
+    fn a_timer_name_with_a_comma_stays_in_its_column() {
+        let csv = to_csv(&[entry("Water, then stretch")]);
+        assert_eq!(
+            csv,
+            "timestamp,kind,outcome,duration_seconds\n\
+             \"2026-10-16T10:00:00+02:00\",\"Water, then stretch\",\"completed\",20\n"
+        );
+    }
+
+    #[test]
+    fn quotes_inside_a_field_are_doubled() {
+        assert_eq!(quote("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(quote("two\nlines"), "\"two\nlines\"");
+    }
+}
@@ -0,0 +1,72 @@
+//! Shell hooks: a user command (`onBreakEndCommand`) run whenever a break ends, e.g. to log
+//! it to a time tracker.
+//!
+//! Nothing is ever run unless `allowShellHooks` is on as well. The command runs through the
+//! platform shell with the user's full permissions, which the settings window warns about.
+//! It gets the break in `NUDGE_BREAK_KIND` (`micro`/`long`), `NUDGE_BREAK_SECONDS` (time
+//! spent on it) and `NUDGE_BREAK_COMPLETED` (`true`/`false`).
+
+use tauri::AppHandle;
+use tauri_plugin_shell::ShellExt;
+use tauri_plugin_store::StoreExt;
+
+use crate::{BreakKind, SETTINGS_STORE};
+
+#[cfg(windows)]
+const SHELL: (&str, &str) = ("cmd", "/C");
+#[cfg(not(windows))]
+const SHELL: (&str, &str) = ("sh", "-c");
+
+/// Runs `onBreakEndCommand` in the background, logging what it printed and how it exited.
+pub fn run_break_end_command(app: &AppHandle, kind: BreakKind, completed: bool, elapsed_seconds: u64) {
+    if !crate::get_bool_setting(app, "allowShellHooks", false) {
+        return;
+    }
+    let command = crate::get_string_setting(app, "onBreakEndCommand", "");
+    let command = command.trim();
+    if command.is_empty() {
+        return;
+    }
+
+    let (shell, flag) = SHELL;
+    let hook = app
+        .shell()
+        .command(shell)
+        .args([flag, command])
+        .env("NUDGE_BREAK_KIND", kind.as_str())
+        .env("NUDGE_BREAK_SECONDS", elapsed_seconds.to_string())
+        .env("NUDGE_BREAK_COMPLETED", completed.to_string());
+    log::info!("🪝 Running break-end command: {}", command);
+    tauri::async_runtime::spawn(async move {
+        match hook.output().await {
+            Ok(output) => {
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                if !stdout.trim().is_empty() {
+                    log::info!("🪝 Break-end command output: {}", stdout.trim());
+                }
+                if !stderr.trim().is_empty() {
+                    log::warn!("⚠️ Break-end command errors: {}", stderr.trim());
+                }
+                if output.status.success() {
+                    log::info!("✅ Break-end command finished");
+                } else {
+                    log::warn!("⚠️ Break-end command exited with {:?}", output.status.code());
+                }
+            }
+            Err(e) => log::error!("❌ Failed to run break-end command: {}", e),
+        }
+    });
+}
+
+/// Stores the break-end command and whether shell hooks may run at all.
+#[tauri::command]
+pub fn set_shell_hook(app: AppHandle, command: String, enabled: bool) -> Result<(), String> {
+    log::debug!("🪝 set_shell_hook command received (enabled: {})", enabled);
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    store.set("onBreakEndCommand", serde_json::json!(command.trim()));
+    store.set("allowShellHooks", serde_json::json!(enabled));
+    store.save().map_err(|e| e.to_string())?;
+    log::info!("✅ Shell hooks {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
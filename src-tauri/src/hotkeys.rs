@@ -0,0 +1,103 @@
+//! Global keyboard shortcuts. The accelerators are configurable from settings, so the
+//! currently registered shortcuts are kept in managed state to be swapped out later.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+pub const DEFAULT_TRIGGER_HOTKEY: &str = "Ctrl+Alt+B";
+pub const DEFAULT_EMERGENCY_HOTKEY: &str = "Ctrl+Alt+Shift+Escape";
+pub const DEFAULT_SETTINGS_HOTKEY: &str = "Ctrl+Alt+Shift+N";
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum HotkeyAction {
+    /// Start a break immediately.
+    TriggerBreak,
+    /// Close the overlay before the break is over.
+    DismissBreak,
+    /// Tear down a stuck overlay, bypassing strict mode.
+    ForceClose,
+    /// Open the settings window; the only way back in when there is no tray icon.
+    OpenSettings,
+}
+
+impl HotkeyAction {
+    /// Settings key holding the accelerator for this action.
+    pub fn setting_key(self) -> &'static str {
+        match self {
+            HotkeyAction::TriggerBreak => "triggerHotkey",
+            HotkeyAction::DismissBreak => "dismissHotkey",
+            HotkeyAction::ForceClose => "emergencyHotkey",
+            HotkeyAction::OpenSettings => "settingsHotkey",
+        }
+    }
+
+    fn run(self, app: &AppHandle) {
+        let app = app.clone();
+        match self {
+            HotkeyAction::TriggerBreak => {
+                tauri::async_runtime::spawn(async move {
+                    let _ = crate::trigger_overlay(app, None).await;
+                });
+            }
+            HotkeyAction::DismissBreak => {
+                if let Err(e) = crate::dismiss_overlay(app) {
+                    log::warn!("⚠️ Dismiss hotkey ignored: {}", e);
+                }
+            }
+            HotkeyAction::ForceClose => crate::force_close_overlay(app),
+            HotkeyAction::OpenSettings => {
+                tauri::async_runtime::spawn(async move {
+                    let _ = crate::open_settings(app).await;
+                });
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct HotkeyState(pub Mutex<HashMap<HotkeyAction, Shortcut>>);
+
+/// Binds `accelerator` to `action`, replacing its previous shortcut. An empty accelerator
+/// just removes the binding. The old binding is only released once the new one is in place,
+/// so a failure leaves it working.
+pub fn register_hotkey(app: &AppHandle, action: HotkeyAction, accelerator: &str) -> Result<(), String> {
+    let state = app.state::<HotkeyState>();
+    let mut bindings = state.0.lock().unwrap();
+    let global_shortcut = app.global_shortcut();
+
+    if accelerator.trim().is_empty() {
+        if let Some(old) = bindings.remove(&action) {
+            let _ = global_shortcut.unregister(old);
+        }
+        log::info!("✅ {:?} hotkey cleared", action);
+        return Ok(());
+    }
+
+    let shortcut: Shortcut = accelerator
+        .parse()
+        .map_err(|e| format!("Invalid shortcut '{}': {}", accelerator, e))?;
+    if bindings.get(&action) == Some(&shortcut) {
+        return Ok(());
+    }
+    if global_shortcut.is_registered(shortcut) {
+        return Err(format!("Shortcut '{}' is already in use", accelerator));
+    }
+
+    global_shortcut
+        .on_shortcut(shortcut, move |app, _shortcut, event| {
+            if event.state != ShortcutState::Pressed {
+                return;
+            }
+            log::info!("⌨️ {:?} hotkey pressed", action);
+            action.run(app);
+        })
+        .map_err(|e| format!("Could not register shortcut '{}': {}", accelerator, e))?;
+
+    if let Some(old) = bindings.insert(action, shortcut) {
+        let _ = global_shortcut.unregister(old);
+    }
+    log::info!("✅ {:?} hotkey registered: {}", action, accelerator);
+    Ok(())
+}
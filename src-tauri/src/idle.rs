@@ -0,0 +1,42 @@
+//! System-wide idle time, used to avoid showing breaks to someone who already stepped away.
+
+/// Whether `system_idle_seconds` reports real idle time on this platform.
+pub const SUPPORTED: bool = cfg!(any(target_os = "windows", target_os = "macos"));
+
+/// Seconds since the last keyboard or mouse input anywhere on the system.
+///
+/// Returns 0 on platforms where idle time can't be queried, so callers treat the user as active.
+#[cfg(target_os = "windows")]
+pub fn system_idle_seconds() -> u64 {
+    use windows_sys::Win32::System::SystemInformation::GetTickCount;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    let mut info = LASTINPUTINFO {
+        cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+        dwTime: 0,
+    };
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return 0;
+    }
+    let now = unsafe { GetTickCount() };
+    u64::from(now.wrapping_sub(info.dwTime) / 1000)
+}
+
+#[cfg(target_os = "macos")]
+pub fn system_idle_seconds() -> u64 {
+    const HID_SYSTEM_STATE: i32 = 1;
+    const ANY_INPUT_EVENT_TYPE: u32 = u32::MAX;
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGEventSourceSecondsSinceLastEventType(source_state_id: i32, event_type: u32) -> f64;
+    }
+
+    let seconds = unsafe { CGEventSourceSecondsSinceLastEventType(HID_SYSTEM_STATE, ANY_INPUT_EVENT_TYPE) };
+    seconds.max(0.0) as u64
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn system_idle_seconds() -> u64 {
+    0
+}
@@ -0,0 +1,154 @@
+//! Built-in text in the user's language.
+//!
+//! `language` picks one of the bundled translations; on first run it is set from the OS
+//! locale. Any string a translation lacks falls back to English. User-defined text such as
+//! `breakMessages` or `reminderTypes` is shown as written, whatever the language.
+
+use std::collections::HashMap;
+
+pub const DEFAULT_LANGUAGE: &str = "en";
+/// Languages with a bundled translation, as ISO 639-1 codes.
+pub const LANGUAGES: &[&str] = &["en", "de", "fr", "es"];
+
+const EN: &[(&str, &str)] = &[
+    ("break.micro.title", "Time for a break."),
+    ("break.micro.message", "Look away from the screen to rest your eyes."),
+    ("break.long.title", "Time for a longer break."),
+    ("break.long.message", "Stand up, stretch, and move around for a few minutes."),
+    ("break.endOfDay.title", "Time to wrap up."),
+    ("break.endOfDay.message", "Your workday is over. Note where you left off and call it a day."),
+    ("break.workedFor", "You've been working for {minutes} minutes."),
+    ("break.workedForMinute", "You've been working for a minute."),
+    ("button.extend", "+1 min"),
+    ("button.snooze", "Snooze 5 min"),
+    ("button.skip", "Skip Break"),
+    ("notification.title", "Nudge"),
+    ("notification.preBreak", "Break in {seconds} seconds"),
+    ("notification.breakFallback", "Time for a break"),
+    ("notification.trayHint", "Nudge is still running in the tray"),
+];
+
+const DE: &[(&str, &str)] = &[
+    ("break.micro.title", "Zeit für eine Pause."),
+    ("break.micro.message", "Schau vom Bildschirm weg, um deine Augen zu entspannen."),
+    ("break.long.title", "Zeit für eine längere Pause."),
+    ("break.long.message", "Steh auf, streck dich und beweg dich ein paar Minuten."),
+    ("break.endOfDay.title", "Zeit, zum Ende zu kommen."),
+    ("break.endOfDay.message", "Dein Arbeitstag ist vorbei. Notiere, wo du stehst, und mach Feierabend."),
+    ("break.workedFor", "Du arbeitest seit {minutes} Minuten."),
+    ("break.workedForMinute", "Du arbeitest seit einer Minute."),
+    ("button.extend", "+1 Min."),
+    ("button.snooze", "5 Min. später"),
+    ("button.skip", "Pause überspringen"),
+    ("notification.preBreak", "Pause in {seconds} Sekunden"),
+    ("notification.breakFallback", "Zeit für eine Pause"),
+    ("notification.trayHint", "Nudge läuft im Infobereich weiter"),
+];
+
+const FR: &[(&str, &str)] = &[
+    ("break.micro.title", "C'est l'heure d'une pause."),
+    ("break.micro.message", "Détournez le regard de l'écran pour reposer vos yeux."),
+    ("break.long.title", "C'est l'heure d'une pause plus longue."),
+    ("break.long.message", "Levez-vous, étirez-vous et bougez quelques minutes."),
+    ("break.endOfDay.title", "Il est temps de conclure."),
+    ("break.endOfDay.message", "Votre journée de travail est terminée. Notez où vous en êtes et arrêtez-vous là."),
+    ("break.workedFor", "Vous travaillez depuis {minutes} minutes."),
+    ("break.workedForMinute", "Vous travaillez depuis une minute."),
+    ("button.extend", "+1 min"),
+    ("button.snooze", "Reporter de 5 min"),
+    ("button.skip", "Passer la pause"),
+    ("notification.preBreak", "Pause dans {seconds} secondes"),
+    ("notification.breakFallback", "C'est l'heure d'une pause"),
+    ("notification.trayHint", "Nudge fonctionne toujours dans la zone de notification"),
+];
+
+const ES: &[(&str, &str)] = &[
+    ("break.micro.title", "Es hora de un descanso."),
+    ("break.micro.message", "Aparta la vista de la pantalla para descansar los ojos."),
+    ("break.long.title", "Es hora de un descanso más largo."),
+    ("break.long.message", "Levántate, estírate y muévete unos minutos."),
+    ("break.endOfDay.title", "Hora de terminar."),
+    ("break.endOfDay.message", "Tu jornada ha terminado. Anota dónde te quedaste y da el día por acabado."),
+    ("break.workedFor", "Llevas {minutes} minutos trabajando."),
+    ("break.workedForMinute", "Llevas un minuto trabajando."),
+    ("button.extend", "+1 min"),
+    ("button.snooze", "Posponer 5 min"),
+    ("button.skip", "Saltar descanso"),
+    ("notification.preBreak", "Descanso en {seconds} segundos"),
+    ("notification.breakFallback", "Es hora de un descanso"),
+    ("notification.trayHint", "Nudge sigue ejecutándose en la bandeja del sistema"),
+];
+
+fn translation(language: &str) -> &'static [(&'static str, &'static str)] {
+    match language {
+        "de" => DE,
+        "fr" => FR,
+        "es" => ES,
+        _ => EN,
+    }
+}
+
+/// Every built-in string in `language`, with English filling any gaps.
+pub fn strings(language: &str) -> HashMap<String, String> {
+    EN.iter()
+        .chain(translation(language))
+        .map(|(key, text)| (key.to_string(), text.to_string()))
+        .collect()
+}
+
+/// The built-in string `key` in the configured language.
+pub fn text(app: &tauri::AppHandle, key: &str) -> String {
+    let language = crate::get_string_setting(app, "language", DEFAULT_LANGUAGE);
+    let find = |table: &[(&str, &'static str)]| table.iter().find(|(k, _)| *k == key).map(|(_, text)| *text);
+    find(translation(&language))
+        .or_else(|| find(EN))
+        .unwrap_or(key)
+        .to_string()
+}
+
+/// The OS locale's language if there is a translation for it, else English.
+pub fn detect_language() -> &'static str {
+    let Some(locale) = os_locale() else {
+        return DEFAULT_LANGUAGE;
+    };
+    // "de_DE.UTF-8", "de-DE" and "de" all mean German.
+    let code = locale
+        .split(['_', '-', '.'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    LANGUAGES
+        .iter()
+        .find(|language| **language == code)
+        .copied()
+        .unwrap_or(DEFAULT_LANGUAGE)
+}
+
+#[cfg(target_os = "windows")]
+fn os_locale() -> Option<String> {
+    use windows_sys::Win32::Globalization::GetUserDefaultLocaleName;
+
+    const LOCALE_NAME_MAX_LENGTH: usize = 85;
+
+    let mut name = [0u16; LOCALE_NAME_MAX_LENGTH];
+    let len = unsafe { GetUserDefaultLocaleName(name.as_mut_ptr(), name.len() as i32) };
+    if len <= 1 {
+        return None;
+    }
+    // The length includes the terminating null.
+    Some(String::from_utf16_lossy(&name[..len as usize - 1]))
+}
+
+#[cfg(not(target_os = "windows"))]
+fn os_locale() -> Option<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+}
+
+#[tauri::command]
+pub fn get_localized_strings(app: tauri::AppHandle) -> HashMap<String, String> {
+    log::debug!("🌐 get_localized_strings command received!");
+    strings(&crate::get_string_setting(&app, "language", DEFAULT_LANGUAGE))
+}
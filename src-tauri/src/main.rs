@@ -3,263 +3,3136 @@
     windows_subsystem = "windows"
 )]
 
+mod appearance;
+mod control;
+mod diagnostics;
+mod end_of_day;
+mod events;
+mod exercises;
+mod fullscreen;
+mod hooks;
+mod history;
+mod hotkeys;
+mod idle;
+mod locale;
+mod messages;
+mod monitors;
+mod one_off;
+mod power;
+mod profiles;
+mod reminders;
+mod remote_overlay;
+mod resume;
+mod schedule;
+mod settings;
+mod settings_watch;
+mod sound;
+mod stats;
+mod time_window;
+mod timers;
+mod tray_icon;
+mod webhook;
+mod weekday;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 use std::time::Duration;
-use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
-use tauri::menu::{Menu, MenuItem};
-use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager, Wry, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use tauri::menu::{Menu, MenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+use tauri_plugin_autostart::ManagerExt;
+use tauri_plugin_notification::NotificationExt;
+use tauri_plugin_opener::OpenerExt;
 use tauri_plugin_store::StoreExt;
-use tokio::time::interval;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+use schedule::{Clock, SystemClock};
 
-const OVERLAY_WINDOW_LABEL: &str = "overlay";
+/// Overlay windows are labelled `overlay-0`, `overlay-1`, ... one per monitor.
+const OVERLAY_WINDOW_LABEL_PREFIX: &str = "overlay-";
 const SETTINGS_WINDOW_LABEL: &str = "settings";
-struct TimerState(pub Mutex<Option<tauri::async_runtime::JoinHandle<()>>>);
+const CONFIRM_EXIT_WINDOW_LABEL: &str = "confirm-exit";
+const SETTINGS_STORE: &str = "settings.json";
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+enum BreakKind {
+    /// Short, frequent eye-rest break.
+    #[default]
+    Micro,
+    /// Longer break shown after every `longBreakEveryNMicroBreaks` micro-breaks.
+    Long,
+    /// Wind-down break at `workEndTime` (`endOfDayBreak`).
+    #[serde(rename = "end-of-day")]
+    EndOfDay,
+}
+
+impl BreakKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            BreakKind::Micro => "micro",
+            BreakKind::Long => "long",
+            BreakKind::EndOfDay => "end-of-day",
+        }
+    }
+
+    /// Page the overlay loads for this kind; the page picks its content from the query.
+    fn overlay_url(self) -> WebviewUrl {
+        WebviewUrl::App(format!("index.html?kind={}", self.as_str()).into())
+    }
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BreakConfig {
+    kind: BreakKind,
+    duration_seconds: u64,
+    /// Whether the overlay may offer a skip button (see `requireBreakConfirmation`).
+    can_skip: bool,
+    /// How opaque the overlay's backdrop is, from 0.0 to 1.0.
+    opacity: f64,
+    /// `strictMode`: the break can only end by running out, so the overlay offers no way out.
+    strict: bool,
+    /// `KeyboardEvent.code` of the key that acknowledges the break (`acknowledgeKey`).
+    acknowledge_key: String,
+    /// How long the overlay takes to fade in (`overlayFadeInMs`).
+    fade_in_ms: u64,
+    /// How long the overlay's fade-out plays before the window is closed (`overlayFadeOutMs`).
+    fade_out_ms: u64,
+    /// What the break is a reminder for, replacing the eye-rest text; `None` for plain breaks.
+    reminder: Option<reminders::ReminderType>,
+    /// `overlayMode`, so the page can lay itself out for the window it is in.
+    mode: appearance::OverlayMode,
+    /// `overlayTheme`, with "auto" already resolved.
+    theme: appearance::Theme,
+    /// Whether the window blurs the desktop behind it, so the page should let it show.
+    blur: bool,
+    /// `overlayBackgroundImage` as a data URL, or `None` for the default backdrop.
+    background_image: Option<String>,
+    /// `accessibilityMode`: render in high contrast and keep every action on the keyboard.
+    accessible: bool,
+    /// Whether this window shows the break or only dims its monitor (`primaryContentOnly`).
+    role: monitors::OverlayRole,
+    /// Whether this is `replay_last_break` showing a past break again.
+    replay: bool,
+    /// Seconds worked since the previous break (or since launch), not counting pauses and
+    /// focus mode; `None` for previews.
+    worked_seconds: Option<u64>,
+}
+
+/// What a real break showed, kept so `replay_last_break` can show it again.
+#[derive(Clone)]
+struct LastBreak {
+    kind: BreakKind,
+    reminder: Option<reminders::ReminderType>,
+    message: Option<String>,
+    exercise: Option<exercises::Exercise>,
+}
+
+/// A running break loop. Cancelling the token stops it at its next await point; the handle
+/// resolves once it has.
+struct TimerLoop {
+    handle: tauri::async_runtime::JoinHandle<()>,
+    cancel: CancellationToken,
+}
+
+impl TimerLoop {
+    /// Spawns `run` as the loop taking over from `previous`. The previous loop is cancelled
+    /// and `run` only starts once it has finished, so there is never more than one live
+    /// loop however quickly loops are replaced.
+    fn replace<F>(previous: Option<TimerLoop>, run: F) -> TimerLoop
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        if let Some(previous) = &previous {
+            previous.cancel.cancel();
+        }
+        let cancel = CancellationToken::new();
+        let handle = tauri::async_runtime::spawn({
+            let cancel = cancel.clone();
+            async move {
+                if let Some(previous) = previous {
+                    let _ = previous.handle.await;
+                }
+                tokio::select! {
+                    biased;
+                    _ = cancel.cancelled() => log::info!("🛑 Timer loop stopped"),
+                    _ = run => {}
+                }
+            }
+        });
+        TimerLoop { handle, cancel }
+    }
+}
+
+/// Which break loop: the regular schedule, or the entry of `timers` with that name. Every
+/// one runs `run_timer_loop` under the same rules.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum TimerId {
+    Regular,
+    Named(String),
+}
+
+impl std::fmt::Display for TimerId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TimerId::Regular => write!(f, "Break timer"),
+            TimerId::Named(name) => write!(f, "Timer '{}'", name),
+        }
+    }
+}
+
+/// A running break loop and when it next fires.
+struct ScheduledLoop {
+    timer_loop: TimerLoop,
+    deadline: Instant,
+    /// `deadline` as a wall-clock time, kept up to date by the sleep watchdog.
+    next_break_at: Option<std::time::SystemTime>,
+}
+
+#[derive(Default)]
+struct Timer {
+    /// The running break loops. A loop missing from here isn't running.
+    loops: HashMap<TimerId, ScheduledLoop>,
+    paused: bool,
+    /// Time that was left until the next nudge when the timer was paused.
+    paused_remaining: Option<Duration>,
+    /// When a pause with `resumeAfterMinutes` ends by itself.
+    resume_at: Option<Instant>,
+    /// Resumes the timer once `resume_at` is reached.
+    resume_handle: Option<tauri::async_runtime::JoinHandle<()>>,
+    /// Snoozes used for the current break; reset once a fresh break cycle begins.
+    snoozes_this_break: u64,
+    /// Postponements used for the current break; reset alongside the snoozes.
+    postponements_this_break: u64,
+    /// Minutes `extend_interval_once` has added to the current interval.
+    interval_extension_minutes: u64,
+    /// Break that was pushed back after it had already started, to be shown again as the
+    /// same kind when the loop next fires.
+    pending_break: Option<BreakKind>,
+    /// Micro-breaks shown since the last long break.
+    micro_breaks_since_long: u64,
+    /// Kind of the break currently (or most recently) shown.
+    current_break: BreakKind,
+    /// When the overlay for the current break opened. Taken once the break's outcome is recorded.
+    break_started_at: Option<Instant>,
+    /// Message picked for the current break, shared by the overlays on every monitor.
+    break_message: Option<String>,
+    /// Exercise picked for the current long break, shared the same way.
+    break_exercise: Option<exercises::Exercise>,
+    /// The last real break this session, for `replay_last_break`.
+    last_break: Option<LastBreak>,
+    /// Set while the preview on screen is a replay of `last_break`.
+    replaying: bool,
+    /// The entry of `timers` the break on screen came from; `None` for the regular schedule.
+    break_timer: Option<String>,
+    /// Reminder type the current micro-break is about, if any are configured.
+    reminder: Option<reminders::ReminderType>,
+    /// While set, focus mode holds back every nudge until this instant.
+    focus_until: Option<Instant>,
+    /// Ends focus mode once `focus_until` is reached.
+    focus_handle: Option<tauri::async_runtime::JoinHandle<()>>,
+    /// Whether the focus hold is a snooze for the rest of the day, ending at midnight.
+    snoozed_until_tomorrow: bool,
+    /// Set by a manual break that should restart the regular interval once it's over.
+    reset_schedule_after_break: bool,
+    /// Whether the overlays on screen are a preview from the settings window.
+    preview: bool,
+    /// Full length of the break on screen.
+    break_duration_seconds: u64,
+    /// Seconds the break countdown has left; 0 once it has run out.
+    break_remaining_seconds: u64,
+    /// Set from the moment a break starts opening its overlays until they are closed.
+    break_active: bool,
+    /// The pomodoro phase in progress, or `None` outside pomodoro mode. `deadline` is when
+    /// it ends.
+    phase: Option<schedule::Phase>,
+    /// Breaks skipped or dismissed in a row; a completed break resets it.
+    consecutive_skips: u64,
+    /// Whether the break on screen was made strict by escalation.
+    escalated_strict: bool,
+    /// How many of the `confirmationPrompts` the current break has had confirmed.
+    confirmations_received: usize,
+    /// Labels of the overlay windows that currently have focus.
+    focused_overlays: HashSet<String>,
+    /// With `primaryContentOnly`, the label of the overlay showing the full break screen.
+    content_overlay: Option<String>,
+    /// Set when focus left the last overlay window, cleared when one gets it back. With
+    /// `pauseDurationWhenUnfocused` the break countdown stands still while this is set.
+    overlay_lost_focus: bool,
+    /// Countdown that ticks the overlay once a second and closes it when the break is over.
+    close_handle: Option<tauri::async_runtime::JoinHandle<()>>,
+    /// Work time in the current stretch from before the work clock was last suspended.
+    worked_before: Duration,
+    /// When the work clock last started running; `None` while paused, in focus mode or on a
+    /// break.
+    working_since: Option<Instant>,
+    /// `worked()` as it stood when the break on screen opened.
+    break_worked_seconds: Option<u64>,
+}
+
+impl Timer {
+    /// When the loop `id` next fires. `None` while it isn't running.
+    fn deadline(&self, id: &TimerId) -> Option<Instant> {
+        self.loops.get(id).map(|running| running.deadline)
+    }
+
+    /// Moves the deadline of the loop `id`, if it is running.
+    fn set_deadline(&mut self, id: &TimerId, deadline: Instant) {
+        if let Some(running) = self.loops.get_mut(id) {
+            running.deadline = deadline;
+        }
+    }
+
+    /// Cancels the break loop `id`, if it is running, and forgets it.
+    fn stop_timer_loop(&mut self, id: &TimerId) {
+        if let Some(running) = self.loops.remove(id) {
+            running.timer_loop.cancel.cancel();
+        }
+        if *id == TimerId::Regular {
+            self.phase = None;
+        }
+    }
+
+    /// Cancels every break loop.
+    fn stop_all_loops(&mut self) {
+        for (_, running) in self.loops.drain() {
+            running.timer_loop.cancel.cancel();
+        }
+        self.phase = None;
+    }
+
+    /// Marks a break as on screen, unless one already is. Returns whether the caller got the
+    /// screen; whoever didn't must not open any overlays. `close_overlay_windows` frees it.
+    fn claim_screen(&mut self) -> bool {
+        if self.break_active {
+            return false;
+        }
+        self.break_active = true;
+        true
+    }
+
+    /// Time worked since the last break taken, leaving out time the work clock was suspended.
+    fn worked(&self) -> Duration {
+        self.worked_before + self.working_since.map_or(Duration::ZERO, |since| since.elapsed())
+    }
+
+    /// Stops the work clock, keeping what it has counted so far.
+    fn suspend_work_clock(&mut self) {
+        if let Some(since) = self.working_since.take() {
+            self.worked_before += since.elapsed();
+        }
+    }
+
+    /// Starts the work clock again, unless something still holds the nudges back.
+    fn resume_work_clock(&mut self) {
+        if self.working_since.is_none() && !self.paused && self.focus_until.is_none() && !self.break_active {
+            self.working_since = Some(Instant::now());
+        }
+    }
+
+    /// Starts a fresh work stretch after a break.
+    fn reset_work_clock(&mut self) {
+        self.worked_before = Duration::ZERO;
+        self.working_since = None;
+        self.resume_work_clock();
+    }
+}
+
+struct TimerState(pub Mutex<Timer>);
+
+/// When this run of the app started and what it has done since, for `get_session_info`.
+/// Managed from `setup`.
+struct SessionState {
+    started_at: Instant,
+    started_at_local: chrono::DateTime<chrono::Local>,
+    breaks_completed: AtomicU64,
+    breaks_skipped: AtomicU64,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionInfo {
+    started_at: String,
+    uptime_seconds: u64,
+    breaks_completed: u64,
+    breaks_skipped: u64,
+    active_profile: Option<String>,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TimerStatus {
+    running: bool,
+    paused: bool,
+    seconds_until_next_break: u64,
+    interval_minutes: u64,
+    /// Seconds of focus mode left, or `None` when it's off.
+    focus_seconds_remaining: Option<u64>,
+    /// Whether nudges are snoozed until midnight; `focus_seconds_remaining` says how long.
+    snoozed_until_tomorrow: bool,
+    /// Seconds until a timed pause resumes by itself, or `None` when paused indefinitely or not at all.
+    auto_resume_seconds: Option<u64>,
+    /// Minutes the next break has been delayed by `extend_interval_once`, or `None` if it hasn't.
+    next_break_delayed_minutes: Option<u64>,
+    /// How far the app has escalated after skipped breaks; 0 when it hasn't.
+    escalation_level: u64,
+}
+
+const TRAY_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+const LOG_MAX_FILE_SIZE: u128 = 1024 * 1024;
+const LOG_FILES_KEPT: usize = 7;
+
+/// Payload of the app-wide `break-started` event.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BreakStarted {
+    kind: BreakKind,
+    duration_seconds: u64,
+}
+
+/// Payload of the app-wide `break-finished` event, sent once a break's outcome is recorded.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BreakFinished {
+    kind: BreakKind,
+    /// False if the break was dismissed or skipped before its countdown ran out.
+    completed: bool,
+    elapsed_seconds: u64,
+}
+
+/// Payload of the `break-tick` event, emitted once a second while a break is showing.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BreakTick {
+    remaining_seconds: u64,
+    duration_seconds: u64,
+    /// Seconds until the break may be ended early (`minimumBreakSeconds`); 0 once it may.
+    grace_remaining_seconds: u64,
+}
+
+/// Payload of the `break-confirmed` event, so every overlay shows the same progress
+/// through the confirmation prompts.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BreakConfirmed {
+    confirmed: usize,
+    total: usize,
+}
+
+const DEFAULT_ACKNOWLEDGE_KEY: &str = "Space";
+const DEFAULT_TRAY_LEFT_CLICK_ACTION: &str = "open-settings";
+const PREVIEW_DURATION_SECONDS: u64 = 10;
+/// Most `extend_interval_once` may push a single break out by, in minutes.
+const MAX_INTERVAL_EXTENSION_MINUTES: u64 = 60;
+/// Pause before the first retry of a failed overlay window; each later retry waits longer.
+const OVERLAY_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+/// The acknowledge key is ignored this soon after a break opens, so a key already held
+/// down when it appeared doesn't end it.
+const ACKNOWLEDGE_GRACE: Duration = Duration::from_secs(1);
+
+/// Focus mode lengths offered in the tray, in minutes.
+const FOCUS_PRESETS_MINUTES: [u64; 3] = [25, 50, 90];
+const FOCUS_MENU_ID_PREFIX: &str = "focus:";
+const FOCUS_CANCEL_MENU_ID: &str = "focus-cancel";
+const SNOOZE_DAY_MENU_ID: &str = "snooze-day";
+
+/// Handles to the tray icon and the menu items whose labels follow the timer state.
+struct TrayState {
+    icon: TrayIcon<Wry>,
+    /// Toggles between pausing and resuming nudges.
+    pause_item: MenuItem<Wry>,
+    /// Lists the saved profiles, with the active one checked.
+    profiles_menu: Submenu<Wry>,
+    /// Focus mode presets, plus `focus_cancel_item` while focus mode is on.
+    focus_menu: Submenu<Wry>,
+    focus_cancel_item: MenuItem<Wry>,
+    /// Snoozes until midnight, or offers to resume while snoozed.
+    snooze_day_item: MenuItem<Wry>,
+}
+
+/// Shows a micro-break right now. With `reset_schedule` (default: the `resetOnManualBreak`
+/// setting) the regular interval starts over once this break is finished; otherwise the
+/// next scheduled break stays where it was.
+#[tauri::command]
+async fn trigger_overlay(app: AppHandle, reset_schedule: Option<bool>) -> Result<(), String> {
+    log::debug!("✅ trigger_overlay command received!");
+    let reset_schedule = reset_schedule.unwrap_or_else(|| get_bool_setting(&app, "resetOnManualBreak", false));
+    if show_overlay_window(&app, BreakKind::Micro).await {
+        app.state::<TimerState>().0.lock().unwrap().reset_schedule_after_break = reset_schedule;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_overlay_duration(app: AppHandle) -> Result<u64, String> {
+    log::debug!("✅ get_overlay_duration command received!");
+    let duration = app.state::<TimerState>().0.lock().unwrap().break_duration_seconds;
+    log::info!("✅ Returning duration: {} seconds", duration);
+    Ok(duration)
+}
+
+#[tauri::command]
+fn get_break_config(app: AppHandle, window: WebviewWindow) -> BreakConfig {
+    log::debug!("✅ get_break_config command received!");
+    let (kind, duration_seconds, preview, reminder, role, replay, worked_seconds) = {
+        let timer_state = app.state::<TimerState>();
+        let timer = timer_state.0.lock().unwrap();
+        let role = match &timer.content_overlay {
+            Some(label) if label != window.label() => monitors::OverlayRole::Dim,
+            _ => monitors::OverlayRole::Content,
+        };
+        (
+            timer.current_break,
+            timer.break_duration_seconds,
+            timer.preview,
+            timer.reminder.clone(),
+            role,
+            timer.preview && timer.replaying,
+            timer.break_worked_seconds,
+        )
+    };
+    let strict = strict_mode(&app) && !preview;
+    BreakConfig {
+        kind,
+        duration_seconds,
+        can_skip: !get_bool_setting(&app, "requireBreakConfirmation", false) && !strict,
+        opacity: overlay_opacity(&app),
+        strict,
+        acknowledge_key: get_string_setting(&app, "acknowledgeKey", DEFAULT_ACKNOWLEDGE_KEY),
+        fade_in_ms: get_u64_setting(&app, "overlayFadeInMs", 300),
+        fade_out_ms: get_u64_setting(&app, "overlayFadeOutMs", 300),
+        reminder,
+        mode: appearance::overlay_mode(&app),
+        theme: appearance::overlay_theme(&app, &window),
+        blur: appearance::overlay_blur(&app),
+        background_image: appearance::overlay_background_image(&app),
+        accessible: get_bool_setting(&app, "accessibilityMode", false),
+        role,
+        replay,
+        worked_seconds,
+    }
+}
+
+#[tauri::command]
+async fn open_settings(app: AppHandle) -> Result<(), String> {
+    log::debug!("✅ open_settings command received!");
+    if let Some(window) = app.get_webview_window(SETTINGS_WINDOW_LABEL) {
+        log::info!("✅ Settings window exists, showing it");
+        let _ = window.show();
+        let _ = window.set_focus();
+    } else {
+        log::info!("✅ Creating new settings window");
+        let _ = WebviewWindowBuilder::new(&app, SETTINGS_WINDOW_LABEL, WebviewUrl::App("settings.html".into()))
+            .title("Nudge Settings")
+            .inner_size(600.0, 700.0)
+            .resizable(false)
+            .center()
+            .build();
+    }
+    Ok(())
+}
+
+/// Opens the folder holding the log files in the system file manager.
+#[tauri::command]
+fn open_log_folder(app: AppHandle) -> Result<(), String> {
+    log::debug!("📂 open_log_folder command received!");
+    let dir = app.path().app_log_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    app.opener()
+        .open_path(dir.to_string_lossy(), None::<&str>)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn exit_app(app: AppHandle) {
+    log::debug!("✅ exit_app command received!");
+    request_exit(&app);
+}
+
+/// Quits, or with `confirmBeforeExit` on asks first in a small window whose Quit button
+/// calls `confirm_exit`.
+fn request_exit(app: &AppHandle) {
+    if !get_bool_setting(app, "confirmBeforeExit", false) {
+        log::info!("🚪 Shutting down");
+        app.exit(0);
+        return;
+    }
+    if let Some(window) = app.get_webview_window(CONFIRM_EXIT_WINDOW_LABEL) {
+        let _ = window.show();
+        let _ = window.set_focus();
+        return;
+    }
+    log::info!("🚪 Asking before shutting down");
+    if let Err(e) = WebviewWindowBuilder::new(app, CONFIRM_EXIT_WINDOW_LABEL, WebviewUrl::App("confirm-exit.html".into()))
+        .title("Quit Nudge?")
+        .inner_size(420.0, 320.0)
+        .resizable(false)
+        .always_on_top(true)
+        .center()
+        .build()
+    {
+        // Better to quit unasked than to leave Exit doing nothing.
+        log::error!("❌ Failed to open the exit confirmation, exiting anyway: {}", e);
+        app.exit(0);
+    }
+}
+
+#[tauri::command]
+fn confirm_exit(app: AppHandle) {
+    log::debug!("🚪 confirm_exit command received!");
+    app.exit(0);
+}
+
+/// Applies changed settings by restarting the timer. If any stored setting is invalid the
+/// timer is left as it is and every problem is returned, so the settings window can flag them.
+#[tauri::command]
+fn restart_timer(app: AppHandle) -> Result<(), Vec<String>> {
+    log::info!("✅ Restarting timer due to settings change.");
+    let store = app.store(SETTINGS_STORE).map_err(|e| vec![e.to_string()])?;
+    if let Err(problems) = settings::validate_settings(&store) {
+        log::error!("❌ Not restarting timer, invalid settings: {:?}", problems);
+        return Err(problems);
+    }
+    settings_watch::mark_applied(&app);
+    start_all_timers(&app);
+    Ok(())
+}
+
+/// Stops the nudges until `resume_timer`, or with `resume_after_minutes` until that long has
+/// passed. Pausing again while paused only changes when (or whether) it resumes by itself.
+#[tauri::command]
+fn pause_timer(app: AppHandle, resume_after_minutes: Option<u64>) -> Result<(), String> {
+    log::debug!("⏸️ pause_timer command received (resume after {:?} minutes)", resume_after_minutes);
+    if resume_after_minutes == Some(0) {
+        return Err("Auto-resume must be at least 1 minute away".to_string());
+    }
+    let resume_after = resume_after_minutes.map(|minutes| Duration::from_secs(minutes * 60));
+    let timer_state = app.state::<TimerState>();
+    let mut timer = timer_state.0.lock().unwrap();
+
+    if timer.paused {
+        drop(timer);
+        log::warn!("⚠️ Timer already paused");
+        schedule_auto_resume(&app, resume_after);
+        refresh_tray(&app);
+        return Ok(());
+    }
+
+    let remaining = timer
+        .deadline(&TimerId::Regular)
+        .map(|deadline| deadline.saturating_duration_since(Instant::now()));
+    timer.stop_all_loops();
+    timer.paused = true;
+    timer.paused_remaining = remaining;
+    timer.suspend_work_clock();
+    drop(timer);
+
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    store.set("paused", serde_json::json!(true));
+    // The remaining time below takes over from any snooze.
+    store.delete("snoozedUntil");
+    match remaining {
+        Some(remaining) => store.set("pausedRemainingSeconds", serde_json::json!(remaining.as_secs())),
+        None => {
+            store.delete("pausedRemainingSeconds");
+        }
+    }
+    store.save().map_err(|e| e.to_string())?;
+
+    log::info!("✅ Timer paused with {:?} remaining", remaining);
+    schedule_auto_resume(&app, resume_after);
+    events::publish(&app, events::LifecycleEvent::Paused);
+    Ok(())
+}
+
+/// Arranges for a pause to end by itself after `after`, replacing any earlier arrangement;
+/// `None` leaves it paused until resumed by hand. The deadline is kept in `pausedUntil` so
+/// it survives a restart.
+fn schedule_auto_resume(app: &AppHandle, after: Option<Duration>) {
+    let handle = after.map(|after| {
+        log::info!("⏰ Nudges resume by themselves in {:?}", after);
+        tauri::async_runtime::spawn({
+            let app = app.clone();
+            async move {
+                tokio::time::sleep(after).await;
+                log::info!("▶️ Pause over, resuming");
+                if let Err(e) = resume_timer(app) {
+                    log::error!("❌ Failed to resume after the pause: {}", e);
+                }
+            }
+        })
+    });
+    {
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        if let Some(old) = std::mem::replace(&mut timer.resume_handle, handle) {
+            old.abort();
+        }
+        timer.resume_at = after.map(|after| Instant::now() + after);
+    }
+    persist_deadline(app, "pausedUntil", after);
+}
+
+#[tauri::command]
+fn resume_timer(app: AppHandle) -> Result<(), String> {
+    log::debug!("▶️ resume_timer command received!");
+    let timer_state = app.state::<TimerState>();
+    let mut timer = timer_state.0.lock().unwrap();
+
+    if !timer.paused {
+        log::warn!("⚠️ Timer is not paused");
+        return Ok(());
+    }
+
+    timer.paused = false;
+    timer.resume_work_clock();
+    let remaining = timer.paused_remaining.take();
+    let focusing = timer.focus_until.is_some();
+    timer.resume_at = None;
+    // Not aborted: this may be running inside that very task.
+    timer.resume_handle.take();
+    drop(timer);
+    persist_deadline(&app, "pausedUntil", None);
+
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    store.set("paused", serde_json::json!(false));
+    store.delete("pausedRemainingSeconds");
+    store.save().map_err(|e| e.to_string())?;
+
+    let interval_minutes = micro_break_interval_minutes(&app);
+    if focusing {
+        log::info!("🎧 Focus mode is on, the timer starts when it ends");
+    } else {
+        start_named_timers(&app);
+        if interval_minutes == 0 {
+            log::warn!("⚠️ Timer interval is 0, not starting timer");
+        } else {
+            let first_delay = remaining.unwrap_or_else(|| break_interval(&app, &SystemClock));
+            spawn_timer_loop(&app, TimerId::Regular, first_delay, &SystemClock);
+        }
+    }
+
+    events::publish(&app, events::LifecycleEvent::Resumed);
+    Ok(())
+}
+
+/// Holds back every nudge for `minutes`, then starts a fresh interval. Unlike pausing, it
+/// ends by itself; starting it again replaces the current focus period.
+#[tauri::command]
+fn start_focus_mode(app: AppHandle, minutes: u64) -> Result<(), String> {
+    log::debug!("🎧 start_focus_mode command received ({} minutes)", minutes);
+    if minutes == 0 {
+        return Err("Focus mode must last at least 1 minute".to_string());
+    }
+
+    begin_focus_mode(&app, Duration::from_secs(minutes * 60), false);
+    log::info!("✅ Focus mode on for {} minutes", minutes);
+    Ok(())
+}
+
+/// Holds back every nudge until the next local midnight, then starts a fresh interval.
+#[tauri::command]
+fn snooze_until_tomorrow(app: AppHandle) -> Result<(), String> {
+    log::debug!("🌙 snooze_until_tomorrow command received!");
+    let now = chrono::Local::now().naive_local();
+    let midnight = (now.date() + chrono::Days::new(1)).and_time(chrono::NaiveTime::MIN);
+    let length = (midnight - now).to_std().map_err(|e| e.to_string())?;
+    begin_focus_mode(&app, length, true);
+    log::info!("✅ Nudges snoozed until tomorrow ({:?})", length);
+    Ok(())
+}
+
+/// Ends a `snooze_until_tomorrow` early.
+#[tauri::command]
+fn cancel_snooze(app: AppHandle) -> Result<(), String> {
+    log::debug!("🌙 cancel_snooze command received!");
+    if !app.state::<TimerState>().0.lock().unwrap().snoozed_until_tomorrow {
+        return Err("Nudges are not snoozed until tomorrow".to_string());
+    }
+    end_focus_mode(&app);
+    log::info!("✅ Snooze until tomorrow cancelled");
+    Ok(())
+}
+
+/// Holds breaks off for `length`, then restarts the timer. With `until_tomorrow` the hold is
+/// the snooze for the rest of the day. Also used at startup to carry on a hold the app was
+/// closed during.
+fn begin_focus_mode(app: &AppHandle, length: Duration, until_tomorrow: bool) {
+    let focus_handle = tauri::async_runtime::spawn({
+        let app = app.clone();
+        async move {
+            tokio::time::sleep(length).await;
+            log::info!("🎧 Focus mode over");
+            end_focus_mode(&app);
+        }
+    });
+
+    let timer_state = app.state::<TimerState>();
+    let mut timer = timer_state.0.lock().unwrap();
+    timer.stop_all_loops();
+    if let Some(old) = timer.focus_handle.replace(focus_handle) {
+        old.abort();
+    }
+    timer.focus_until = Some(Instant::now() + length);
+    timer.snoozed_until_tomorrow = until_tomorrow;
+    timer.suspend_work_clock();
+    drop(timer);
+
+    let (key, other_key) = if until_tomorrow {
+        ("snoozedUntilTomorrow", "focusUntil")
+    } else {
+        ("focusUntil", "snoozedUntilTomorrow")
+    };
+    persist_deadline(app, other_key, None);
+    persist_deadline(app, key, Some(length));
+    refresh_tray(app);
+}
+
+#[tauri::command]
+fn cancel_focus_mode(app: AppHandle) -> Result<(), String> {
+    log::debug!("🎧 cancel_focus_mode command received!");
+    if app.state::<TimerState>().0.lock().unwrap().focus_until.is_none() {
+        return Err("Focus mode is not on".to_string());
+    }
+    end_focus_mode(&app);
+    log::info!("✅ Focus mode cancelled");
+    Ok(())
+}
+
+fn end_focus_mode(app: &AppHandle) {
+    {
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        timer.focus_until = None;
+        timer.snoozed_until_tomorrow = false;
+        timer.resume_work_clock();
+        if let Some(handle) = timer.focus_handle.take() {
+            handle.abort();
+        }
+    }
+    persist_deadline(app, "focusUntil", None);
+    persist_deadline(app, "snoozedUntilTomorrow", None);
+    start_all_timers(app);
+}
+
+/// Saves `key` as a Unix timestamp `remaining` from now, or removes it for `None`, so
+/// snoozes and focus sessions survive a restart.
+fn persist_deadline(app: &AppHandle, key: &str, remaining: Option<Duration>) {
+    let store = match app.store(SETTINGS_STORE) {
+        Ok(store) => store,
+        Err(e) => {
+            log::error!("❌ Failed to open the settings store: {}", e);
+            return;
+        }
+    };
+    match remaining {
+        Some(remaining) => {
+            let at = chrono::Utc::now().timestamp() as u64 + remaining.as_secs();
+            store.set(key, serde_json::json!(at));
+        }
+        None => {
+            if !store.delete(key) {
+                return;
+            }
+        }
+    }
+    if let Err(e) = store.save() {
+        log::error!("❌ Failed to save {}: {}", key, e);
+    }
+}
+
+/// How long until the timestamp saved under `key` by `persist_deadline`, or `None` if there
+/// is none or it has already passed.
+fn stored_deadline_remaining(app: &AppHandle, key: &str) -> Option<Duration> {
+    let at = get_u64_setting(app, key, 0);
+    let now = chrono::Utc::now().timestamp() as u64;
+    (at > now).then(|| Duration::from_secs(at - now))
+}
+
+#[tauri::command]
+fn snooze_break(app: AppHandle, minutes: u64) -> Result<(), String> {
+    log::debug!("😴 snooze_break command received ({} minutes)", minutes);
+    if close_preview(&app) {
+        return Ok(());
+    }
+    if minutes == 0 {
+        return Err("Snooze length must be at least 1 minute".to_string());
+    }
+
+    reject_if_strict_break(&app)?;
+    reject_if_timer_break(&app)?;
+
+    let max_snoozes = get_u64_setting(&app, "maxSnoozesPerBreak", 3);
+    let timer_state = app.state::<TimerState>();
+    let mut timer = timer_state.0.lock().unwrap();
+
+    if timer.paused {
+        return Err("Nudges are paused".to_string());
+    }
+    if timer.snoozes_this_break >= max_snoozes {
+        log::warn!("🚫 Snooze limit of {} reached for this break", max_snoozes);
+        return Err(format!("You've already snoozed this break {} times", max_snoozes));
+    }
+
+    timer.snoozes_this_break += 1;
+    drop(timer);
+
+    reschedule_current_break(&app, Duration::from_secs(minutes * 60));
+    log::info!("✅ Break snoozed for {} minutes", minutes);
+    Ok(())
+}
+
+/// Pushes the imminent or active break back by `minutes` without touching the regular interval.
+/// An active break is closed and comes back `minutes` from now; one that hasn't started yet
+/// moves `minutes` past its due time.
+#[tauri::command]
+fn postpone_break(app: AppHandle, minutes: u64) -> Result<(), String> {
+    log::debug!("⏭️ postpone_break command received ({} minutes)", minutes);
+    if close_preview(&app) {
+        return Ok(());
+    }
+    if minutes == 0 {
+        return Err("Postponement must be at least 1 minute".to_string());
+    }
+
+    reject_if_strict_break(&app)?;
+    reject_if_timer_break(&app)?;
+
+    let max_postponements = get_u64_setting(&app, "maxPostponements", 3);
+    let overlay_showing = !overlay_windows(&app).is_empty();
+    let timer_state = app.state::<TimerState>();
+    let mut timer = timer_state.0.lock().unwrap();
+
+    if timer.paused {
+        return Err("Nudges are paused".to_string());
+    }
+    if timer.postponements_this_break >= max_postponements {
+        log::warn!("🚫 Postponement limit of {} reached for this break", max_postponements);
+        return Err(format!("You've already postponed this break {} times", max_postponements));
+    }
+
+    let postponement = Duration::from_secs(minutes * 60);
+    let delay = if overlay_showing {
+        postponement
+    } else {
+        let Some(deadline) = timer.deadline(&TimerId::Regular) else {
+            return Err("No break is scheduled".to_string());
+        };
+        deadline.saturating_duration_since(Instant::now()) + postponement
+    };
+    timer.postponements_this_break += 1;
+    drop(timer);
+
+    reschedule_current_break(&app, delay);
+    log::info!("✅ Break postponed, now due in {:?}", delay);
+    Ok(())
+}
+
+/// Pushes the next break out by `extra_minutes`, before it is due, leaving the stored interval
+/// and the breaks after it alone. All extensions of one interval together are capped at
+/// `MAX_INTERVAL_EXTENSION_MINUTES`; the minutes actually added are returned.
+#[tauri::command]
+fn extend_interval_once(app: AppHandle, extra_minutes: u64) -> Result<u64, String> {
+    log::debug!("⏩ extend_interval_once command received ({} minutes)", extra_minutes);
+    if extra_minutes == 0 {
+        return Err("The extension must be at least 1 minute".to_string());
+    }
+    let timer_state = app.state::<TimerState>();
+    let mut timer = timer_state.0.lock().unwrap();
+    if timer.paused {
+        return Err("Nudges are paused".to_string());
+    }
+    if timer.break_active || timer.phase == Some(schedule::Phase::Break) {
+        return Err("A break is already showing, snooze it instead".to_string());
+    }
+    if timer.focus_until.is_some() {
+        return Err("Focus mode is on".to_string());
+    }
+    let Some(deadline) = timer.deadline(&TimerId::Regular) else {
+        return Err("No break is scheduled".to_string());
+    };
+    let extra_minutes = extra_minutes.min(MAX_INTERVAL_EXTENSION_MINUTES - timer.interval_extension_minutes);
+    if extra_minutes == 0 {
+        log::warn!("🚫 This break is already delayed by the most allowed");
+        return Err(format!("The next break can't be delayed by more than {} minutes", MAX_INTERVAL_EXTENSION_MINUTES));
+    }
+    timer.interval_extension_minutes += extra_minutes;
+    let delay = deadline.saturating_duration_since(Instant::now()) + Duration::from_secs(extra_minutes * 60);
+    drop(timer);
+
+    spawn_timer_loop(&app, TimerId::Regular, delay, &SystemClock);
+    log::info!("✅ Next break delayed by {} minutes, now due in {:?}", extra_minutes, delay);
+    Ok(extra_minutes)
+}
+
+/// Restarts the loop so the current break fires after `delay`, followed by the regular interval.
+/// If the break is on screen it is closed without being recorded, and comes back as the same kind.
+fn reschedule_current_break(app: &AppHandle, delay: Duration) {
+    let overlay_showing = !overlay_windows(app).is_empty();
+    let (kind, elapsed) = {
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        let mut elapsed = Duration::ZERO;
+        let kind = if overlay_showing {
+            timer.pending_break = Some(timer.current_break);
+            // The break comes back later, so closing the overlay now shouldn't count as taking it.
+            elapsed = timer.break_started_at.take().map_or(Duration::ZERO, |started| started.elapsed());
+            timer.current_break
+        } else {
+            timer.pending_break.unwrap_or(BreakKind::Micro)
+        };
+        // Stopped now so it can't fire while the overlay closes; the replacement waits for it.
+        if let Some(running) = timer.loops.get(&TimerId::Regular) {
+            running.timer_loop.cancel.cancel();
+        }
+        (kind, elapsed)
+    };
+
+    if let Err(e) = stats::record_break(app, kind.as_str(), stats::BreakOutcome::Snoozed, elapsed) {
+        log::error!("❌ Failed to record snooze: {}", e);
+    }
+    fade_out_overlay_windows(app);
+    persist_deadline(app, "snoozedUntil", Some(delay));
+
+    spawn_timer_loop(app, TimerId::Regular, delay, &SystemClock);
+}
+
+#[tauri::command]
+fn update_hotkey(app: AppHandle, shortcut: String) -> Result<(), String> {
+    log::debug!("⌨️ update_hotkey command received: {}", shortcut);
+    save_hotkey(&app, hotkeys::HotkeyAction::TriggerBreak, &shortcut)
+}
+
+#[tauri::command]
+fn update_dismiss_hotkey(app: AppHandle, shortcut: String) -> Result<(), String> {
+    log::debug!("⌨️ update_dismiss_hotkey command received: {}", shortcut);
+    save_hotkey(&app, hotkeys::HotkeyAction::DismissBreak, &shortcut)
+}
+
+fn save_hotkey(app: &AppHandle, action: hotkeys::HotkeyAction, shortcut: &str) -> Result<(), String> {
+    hotkeys::register_hotkey(app, action, shortcut)?;
+
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    store.set(action.setting_key(), serde_json::json!(shortcut));
+    store.save().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Registers or removes the OS autostart entry. Does nothing if it's already in that state.
+fn apply_autostart(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    if autolaunch.is_enabled().map_err(|e| e.to_string())? == enabled {
+        return Ok(());
+    }
+    let result = if enabled { autolaunch.enable() } else { autolaunch.disable() };
+    result.map_err(|e| format!("Failed to update autostart: {}", e))?;
+    log::info!("🚀 Autostart {}", if enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+/// Turns launching at login on or off, keeping the OS entry and `autoStart` in step. Nothing is
+/// stored if the OS registration fails, so the UI can put its toggle back.
+#[tauri::command]
+fn set_autostart(app: AppHandle, enabled: bool) -> Result<(), String> {
+    log::debug!("🚀 set_autostart command received: {}", enabled);
+    apply_autostart(&app, enabled)?;
+
+    let saved = app.store(SETTINGS_STORE).map_err(|e| e.to_string()).and_then(|store| {
+        store.set("autoStart", serde_json::json!(enabled));
+        store.save().map_err(|e| e.to_string())
+    });
+    if let Err(e) = saved {
+        if let Err(revert) = apply_autostart(&app, !enabled) {
+            log::error!("❌ Failed to revert autostart: {}", revert);
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Whether the OS really has the app registered to launch at login. This can differ from
+/// the stored `autoStart` if the entry was changed outside the app.
+#[tauri::command]
+fn get_autostart_status(app: AppHandle) -> Result<bool, String> {
+    log::debug!("🚀 get_autostart_status command received!");
+    app.autolaunch().is_enabled().map_err(|e| e.to_string())
+}
+
+/// Closes the overlays if they are only a preview. Every command that would otherwise end
+/// or move the break (and so touch the schedule or the stats) calls this first.
+fn close_preview(app: &AppHandle) -> bool {
+    if !app.state::<TimerState>().0.lock().unwrap().preview {
+        return false;
+    }
+    log::info!("👀 Closing the preview");
+    close_overlay_windows(app);
+    true
+}
+
+/// Shows the last real break of this session again, with the same message, reminder and
+/// exercise, for `PREVIEW_DURATION_SECONDS`. Like a preview it leaves the schedule and the
+/// stats alone.
+#[tauri::command]
+async fn replay_last_break(app: AppHandle) -> Result<(), String> {
+    log::debug!("🔁 replay_last_break command received!");
+    let Some(kind) = app.state::<TimerState>().0.lock().unwrap().last_break.as_ref().map(|last| last.kind) else {
+        return Err("There has been no break to replay yet".to_string());
+    };
+    if !open_overlays_with(&app, kind, true, true, None).await {
+        return Err("A break is already showing".to_string());
+    }
+    Ok(())
+}
+
+/// Shows the overlay for `PREVIEW_DURATION_SECONDS` without touching the schedule, the
+/// stats or strict mode, so its look can be checked from the settings window.
+#[tauri::command]
+async fn preview_overlay(app: AppHandle) -> Result<(), String> {
+    log::debug!("👀 preview_overlay command received!");
+    if !open_overlays(&app, BreakKind::Micro, true, None).await {
+        return Err("A break is already showing".to_string());
+    }
+    Ok(())
+}
+
+/// Whether a break overlay is on screen. Only true while `break_active` is set, so this and
+/// the guard in `open_overlays` always agree; while a break's windows are still being
+/// created it is already active but not yet visible.
+#[tauri::command]
+fn is_overlay_visible(app: AppHandle) -> bool {
+    log::debug!("👁️ is_overlay_visible command received!");
+    if !app.state::<TimerState>().0.lock().unwrap().break_active {
+        return false;
+    }
+    overlay_windows(&app)
+        .iter()
+        .any(|window| window.is_visible().unwrap_or(false))
+}
+
+fn strict_mode(app: &AppHandle) -> bool {
+    get_bool_setting(app, "strictMode", false) || app.state::<TimerState>().0.lock().unwrap().escalated_strict
+}
+
+/// One level for every `escalateAfterSkips` breaks skipped in a row, or 0 with escalation off.
+fn escalation_level(app: &AppHandle) -> u64 {
+    let threshold = get_u64_setting(app, "escalateAfterSkips", 0);
+    if threshold == 0 {
+        return 0;
+    }
+    app.state::<TimerState>().0.lock().unwrap().consecutive_skips / threshold
+}
+
+/// In strict mode a break on screen only ends when its countdown does, so every command
+/// that would end or move it early (dismiss, skip, snooze, postpone) checks this first.
+fn reject_if_strict_break(app: &AppHandle) -> Result<(), String> {
+    if strict_mode(app) && !overlay_windows(app).is_empty() {
+        log::warn!("🔒 Strict mode: refusing to end the break early");
+        return Err("Strict mode is on, this break can't be ended early".to_string());
+    }
+    Ok(())
+}
+
+/// `minimumBreakSeconds`, or 0 in `accessibilityMode`: a screen reader doesn't announce when
+/// the lock lifts, so its users would otherwise find the way out refused without knowing why.
+fn minimum_break_seconds(app: &AppHandle) -> u64 {
+    if get_bool_setting(app, "accessibilityMode", false) {
+        return 0;
+    }
+    get_u64_setting(app, "minimumBreakSeconds", 0)
+}
+
+/// Seconds left of `minimumBreakSeconds` for a break that opened at `started_at`.
+fn minimum_break_remaining(started_at: Option<Instant>, minimum_seconds: u64) -> u64 {
+    let Some(started_at) = started_at else {
+        return 0;
+    };
+    Duration::from_secs(minimum_seconds)
+        .saturating_sub(started_at.elapsed())
+        .as_secs_f64()
+        .ceil() as u64
+}
+
+/// Refuses to end a break early before `minimumBreakSeconds` have passed since it opened.
+fn reject_during_minimum_break(app: &AppHandle) -> Result<(), String> {
+    let minimum = minimum_break_seconds(app);
+    let started_at = app.state::<TimerState>().0.lock().unwrap().break_started_at;
+    let left = minimum_break_remaining(started_at, minimum);
+    if left > 0 {
+        log::warn!("⏳ Refusing to end the break during its first {}s", minimum);
+        return Err(format!("This break can be ended in {} seconds", left));
+    }
+    Ok(())
+}
+
+/// (Re)binds every hotkey from its stored accelerator. Failures are logged per hotkey.
+fn register_hotkeys_from_store(app: &AppHandle) {
+    for action in [
+        hotkeys::HotkeyAction::TriggerBreak,
+        hotkeys::HotkeyAction::DismissBreak,
+        hotkeys::HotkeyAction::ForceClose,
+        hotkeys::HotkeyAction::OpenSettings,
+    ] {
+        let accelerator = get_string_setting(app, action.setting_key(), "");
+        if let Err(e) = hotkeys::register_hotkey(app, action, &accelerator) {
+            log::error!("❌ {}", e);
+        }
+    }
+}
+
+/// Skips the current break, recording it as skipped, and starts a fresh interval from now.
+/// Refused in strict mode.
+#[tauri::command]
+fn skip_break(app: AppHandle) -> Result<(), String> {
+    log::debug!("⏭️ skip_break command received!");
+    if close_preview(&app) {
+        return Ok(());
+    }
+    reject_if_strict_break(&app)?;
+    reject_during_minimum_break(&app)?;
+    if get_bool_setting(&app, "requireBreakConfirmation", false) {
+        return Err("Skipping breaks is disabled".to_string());
+    }
+
+    if overlay_windows(&app).is_empty() {
+        return Err("No break is showing".to_string());
+    }
+    finish_break(&app, false);
+    fade_out_overlay_windows(&app);
+
+    restart_after_break(app);
+    Ok(())
+}
+
+/// Ends the break from the keyboard (`acknowledgeKey`). Once the countdown has run out this
+/// completes the break; before that it counts as dismissing it early, with the same rules,
+/// so strict mode only lets it through after the full duration.
+#[tauri::command]
+fn acknowledge_break(app: AppHandle) -> Result<(), String> {
+    log::debug!("⌨️ acknowledge_break command received!");
+    if close_preview(&app) {
+        return Ok(());
+    }
+    let (started_at, remaining) = {
+        let timer_state = app.state::<TimerState>();
+        let timer = timer_state.0.lock().unwrap();
+        (timer.break_started_at, timer.break_remaining_seconds)
+    };
+    let Some(started_at) = started_at else {
+        return Err("No break is showing".to_string());
+    };
+    if started_at.elapsed() < ACKNOWLEDGE_GRACE {
+        return Err("The break only just started".to_string());
+    }
+    let total = confirmation_prompts(&app).len();
+    let confirmed = app.state::<TimerState>().0.lock().unwrap().confirmations_received;
+    if confirmed < total {
+        return Err(format!("Confirm all {} prompts first ({} done)", total, confirmed));
+    }
+    if remaining > 0 {
+        return dismiss_overlay(app);
+    }
+
+    finish_break(&app, true);
+    fade_out_overlay_windows(&app);
+    restart_after_break(app);
+    Ok(())
+}
+
+fn confirmation_prompts(app: &AppHandle) -> Vec<String> {
+    get_setting(app, "confirmationPrompts")
+        .and_then(|v| serde_json::from_value::<Vec<String>>(v).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|prompt| !prompt.trim().is_empty())
+        .collect()
+}
+
+/// The statements ("I rested my eyes", ...) to confirm, in order, before the break can be
+/// acknowledged.
+#[tauri::command]
+fn get_confirmation_prompts(app: AppHandle) -> Vec<String> {
+    log::debug!("✔️ get_confirmation_prompts command received!");
+    confirmation_prompts(&app)
+}
+
+/// Confirms prompt `index` of the current break. Prompts must be confirmed in order; the
+/// number confirmed so far is returned and broadcast as `break-confirmed`.
+#[tauri::command]
+fn confirm_break_prompt(app: AppHandle, index: usize) -> Result<usize, String> {
+    log::debug!("✔️ confirm_break_prompt command received ({})", index);
+    let total = confirmation_prompts(&app).len();
+    let timer_state = app.state::<TimerState>();
+    let mut timer = timer_state.0.lock().unwrap();
+    if !timer.break_active {
+        return Err("No break is showing".to_string());
+    }
+    if index >= total {
+        return Err(format!("There is no prompt {}", index + 1));
+    }
+    if index > timer.confirmations_received {
+        return Err("Confirm the earlier prompts first".to_string());
+    }
+    if index == timer.confirmations_received {
+        timer.confirmations_received += 1;
+    }
+    let confirmed = timer.confirmations_received;
+    drop(timer);
+
+    log::info!("✔️ Prompt {}/{} confirmed", confirmed, total);
+    if let Err(e) = app.emit("break-confirmed", BreakConfirmed { confirmed, total }) {
+        log::error!("❌ Failed to emit break-confirmed: {}", e);
+    }
+    Ok(confirmed)
+}
+
+/// Adds `seconds` to the break on screen, up to a total length of `maxBreakDurationSeconds`.
+/// Returns the new remaining time, which the overlay is sent straight away.
+#[tauri::command]
+fn extend_break(app: AppHandle, seconds: u64) -> Result<u64, String> {
+    log::debug!("➕ extend_break command received ({} seconds)", seconds);
+    if seconds == 0 {
+        return Err("Extension must be at least 1 second".to_string());
+    }
+    let max_duration = get_u64_setting(&app, "maxBreakDurationSeconds", 1800);
+    let minimum_seconds = minimum_break_seconds(&app);
+    let tick = {
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        if !timer.break_active {
+            return Err("No break is showing".to_string());
+        }
+        if timer.preview {
+            return Err("A preview can't be extended".to_string());
+        }
+        if timer.break_started_at.is_none() {
+            return Err("The break is already over".to_string());
+        }
+        let duration = (timer.break_duration_seconds + seconds).min(max_duration);
+        if duration <= timer.break_duration_seconds {
+            return Err(format!("Breaks can't be longer than {} seconds", max_duration));
+        }
+        timer.break_remaining_seconds += duration - timer.break_duration_seconds;
+        timer.break_duration_seconds = duration;
+        BreakTick {
+            remaining_seconds: timer.break_remaining_seconds,
+            duration_seconds: duration,
+            grace_remaining_seconds: minimum_break_remaining(timer.break_started_at, minimum_seconds),
+        }
+    };
+    let remaining_seconds = tick.remaining_seconds;
+    emit_break_tick(&app, tick);
+    log::info!("✅ Break extended, {}s left", remaining_seconds);
+    Ok(remaining_seconds)
+}
+
+/// Makes the micro-break on screen `seconds` long in total, keeping the time already spent
+/// on it, and ticks the overlay so it shows the new countdown. Returns the seconds left, or
+/// `None` when no real micro-break is running. Never ends the break outright: at least a
+/// second is left for the countdown to finish on.
+fn resize_current_break(app: &AppHandle, seconds: u64) -> Option<u64> {
+    let max_duration = get_u64_setting(app, "maxBreakDurationSeconds", 1800);
+    let minimum_seconds = minimum_break_seconds(app);
+    let tick = {
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        if !timer.break_active || timer.preview || timer.break_started_at.is_none() {
+            return None;
+        }
+        if timer.current_break != BreakKind::Micro {
+            return None;
+        }
+        let duration = seconds.min(max_duration);
+        let elapsed = timer.break_duration_seconds.saturating_sub(timer.break_remaining_seconds);
+        timer.break_remaining_seconds = duration.saturating_sub(elapsed).max(1);
+        timer.break_duration_seconds = duration.max(elapsed + 1);
+        BreakTick {
+            remaining_seconds: timer.break_remaining_seconds,
+            duration_seconds: timer.break_duration_seconds,
+            grace_remaining_seconds: minimum_break_remaining(timer.break_started_at, minimum_seconds),
+        }
+    };
+    let remaining_seconds = tick.remaining_seconds;
+    emit_break_tick(app, tick);
+    log::info!("✅ Break resized to {}s, {}s left", seconds, remaining_seconds);
+    Some(remaining_seconds)
+}
+
+/// Ends the current break early and starts a fresh interval from now.
+///
+/// Refused in strict mode. The dismiss hotkey goes through here too, so it stops working
+/// as well; there is no other way to end a strict break early.
+#[tauri::command]
+fn dismiss_overlay(app: AppHandle) -> Result<(), String> {
+    log::debug!("⏹️ dismiss_overlay command received!");
+    if close_preview(&app) {
+        return Ok(());
+    }
+    reject_if_strict_break(&app)?;
+    reject_during_minimum_break(&app)?;
+    if !get_bool_setting(&app, "allowEarlyDismiss", true) {
+        return Err("Early dismissal is disabled".to_string());
+    }
+
+    if overlay_windows(&app).is_empty() {
+        return Err("No break is showing".to_string());
+    }
+    finish_break(&app, false);
+    fade_out_overlay_windows(&app);
+
+    restart_after_break(app);
+    Ok(())
+}
+
+/// Starts a fresh interval once a break has been ended early. A break from one of the extra
+/// `timers`, or the end-of-day break, leaves the regular schedule alone.
+fn restart_after_break(app: AppHandle) {
+    {
+        let timer_state = app.state::<TimerState>();
+        let timer = timer_state.0.lock().unwrap();
+        if timer.break_timer.is_some() || timer.current_break == BreakKind::EndOfDay {
+            return;
+        }
+    }
+    start_timer(&app, TimerId::Regular);
+}
+
+/// Breaks from the extra `timers` come round again on their own, and the end-of-day break
+/// only comes once, so there's nothing to snooze.
+fn reject_if_timer_break(app: &AppHandle) -> Result<(), String> {
+    let timer_state = app.state::<TimerState>();
+    let timer = timer_state.0.lock().unwrap();
+    match &timer.break_timer {
+        Some(name) if timer.break_active => Err(format!("The '{}' reminder can't be snoozed", name)),
+        None if timer.break_active && timer.current_break == BreakKind::EndOfDay => {
+            Err("The end-of-day break can't be snoozed".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Escape hatch for an overlay that won't go away: destroys every overlay window whatever
+/// strict mode says, stops the break's timers and starts a fresh interval. The break counts
+/// as skipped.
+#[tauri::command]
+fn force_close_overlay(app: AppHandle) {
+    log::warn!("🆘 FORCE-CLOSING ALL OVERLAYS, bypassing strict mode");
+    finish_break(&app, false);
+    close_overlay_windows(&app);
+    {
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        timer.break_remaining_seconds = 0;
+        timer.focused_overlays.clear();
+        timer.overlay_lost_focus = false;
+    }
+    restart_after_break(app);
+}
+
+#[tauri::command]
+fn is_in_dnd(app: AppHandle) -> bool {
+    in_dnd_window(&app)
+}
+
+/// Whether the current local time falls inside one of the `dndSchedules` windows.
+fn in_dnd_window(app: &AppHandle) -> bool {
+    schedule::ScheduleRules::load(app).in_dnd(SystemClock.local())
+}
+
+/// When work hours next begin, as a local ISO timestamp. `None` while inside work hours
+/// or when no work hours are configured.
+#[tauri::command]
+fn next_work_window_start(app: AppHandle) -> Option<String> {
+    let now = chrono::Local::now().naive_local();
+    let window = work_window(&weekday::WeekdaySettings::load(&app))?;
+    if window.contains(now) {
+        return None;
+    }
+    window.next_start(now).map(|start| start.format("%Y-%m-%dT%H:%M:%S").to_string())
+}
+
+/// Work hours from `workStartTime`/`workEndTime`/`workDays`, or `None` if unrestricted.
+/// The hours are today's, `weekdayOverrides` included.
+fn work_window(settings: &weekday::WeekdaySettings) -> Option<time_window::TimeWindow> {
+    let setting = time_window::TimeWindowSetting {
+        start: settings.string_on("workStartTime", today())?.to_string(),
+        end: settings.string_on("workEndTime", today())?.to_string(),
+        days: settings
+            .get("workDays", today())
+            .and_then(|v| serde_json::from_value(v.clone()).ok())
+            .unwrap_or_default(),
+    };
+    if setting.start.is_empty() || setting.end.is_empty() {
+        return None;
+    }
+    match time_window::TimeWindow::from_setting(&setting) {
+        Ok(window) => Some(window),
+        Err(e) => {
+            log::error!("❌ Ignoring invalid work hours: {}", e);
+            None
+        }
+    }
+}
+
+/// Breaks still allowed today under `maxBreaksPerDay`, or `None` if there is no quota.
+fn breaks_left_today(app: &AppHandle) -> Option<u64> {
+    let quota = get_u64_setting(app, "maxBreaksPerDay", 0);
+    (quota > 0).then(|| quota.saturating_sub(stats::completed_today(app)))
+}
+
+/// How many more breaks today's quota allows. `null` when `maxBreaksPerDay` is 0 (no quota).
+#[tauri::command]
+fn breaks_remaining_today(app: AppHandle) -> Option<u64> {
+    log::debug!("🎯 breaks_remaining_today command received!");
+    breaks_left_today(&app)
+}
+
+#[tauri::command]
+fn get_timer_status(app: AppHandle) -> TimerStatus {
+    let interval_minutes = micro_break_interval_minutes(&app);
+    let escalation_level = escalation_level(&app);
+    let timer_state = app.state::<TimerState>();
+    let timer = timer_state.0.lock().unwrap();
+    let seconds_until_next_break = match (timer.paused, timer.deadline(&TimerId::Regular)) {
+        (true, _) => timer.paused_remaining.map_or(0, |remaining| remaining.as_secs()),
+        (false, Some(deadline)) => deadline.saturating_duration_since(Instant::now()).as_secs(),
+        (false, None) => 0,
+    };
+    TimerStatus {
+        running: timer.loops.contains_key(&TimerId::Regular) && !timer.paused,
+        paused: timer.paused,
+        seconds_until_next_break,
+        interval_minutes,
+        focus_seconds_remaining: timer
+            .focus_until
+            .map(|until| until.saturating_duration_since(Instant::now()).as_secs()),
+        snoozed_until_tomorrow: timer.snoozed_until_tomorrow,
+        auto_resume_seconds: timer
+            .resume_at
+            .map(|at| at.saturating_duration_since(Instant::now()).as_secs()),
+        next_break_delayed_minutes: Some(timer.interval_extension_minutes).filter(|minutes| *minutes > 0),
+        escalation_level,
+    }
+}
+
+/// What this run of the app has been up to, for dashboards and for working out why a
+/// break didn't fire.
+#[tauri::command]
+fn get_session_info(app: AppHandle) -> SessionInfo {
+    log::debug!("📊 get_session_info command received!");
+    let session = app.state::<SessionState>();
+    SessionInfo {
+        started_at: session.started_at_local.to_rfc3339(),
+        uptime_seconds: session.started_at.elapsed().as_secs(),
+        breaks_completed: session.breaks_completed.load(Ordering::Relaxed),
+        breaks_skipped: session.breaks_skipped.load(Ordering::Relaxed),
+        active_profile: profiles::active_profile(&app),
+    }
+}
+
+fn toggle_pause(app: &AppHandle) -> Result<(), String> {
+    let paused = app.state::<TimerState>().0.lock().unwrap().paused;
+    if paused {
+        resume_timer(app.clone())
+    } else {
+        pause_timer(app.clone(), None)
+    }
+}
+
+fn tray_tooltip(timer: &Timer) -> String {
+    if let (true, Some(resume_at)) = (timer.paused, timer.resume_at) {
+        let seconds = resume_at.saturating_duration_since(Instant::now()).as_secs();
+        return format!("Paused (auto-resume in {}m)", seconds.div_ceil(60));
+    }
+    if timer.paused {
+        return "Paused".to_string();
+    }
+    if timer.snoozed_until_tomorrow {
+        return "Snoozed until tomorrow".to_string();
+    }
+    if let Some(until) = timer.focus_until {
+        let seconds = until.saturating_duration_since(Instant::now()).as_secs();
+        return format!("Focus mode for {}m", seconds.div_ceil(60));
+    }
+    match (timer.phase, timer.deadline(&TimerId::Regular)) {
+        (Some(phase), Some(deadline)) => {
+            let seconds = deadline.saturating_duration_since(Instant::now()).as_secs();
+            format!("{}: {}m left", phase.label(), seconds.div_ceil(60))
+        }
+        (None, Some(deadline)) => {
+            let seconds = deadline.saturating_duration_since(Instant::now()).as_secs();
+            format!("Next break in {}m", seconds.div_ceil(60))
+        }
+        (_, None) => "Disabled".to_string(),
+    }
+}
+
+/// Does what `trayLeftClickAction` asks for when the tray icon itself is left-clicked; the
+/// menu stays on the right button.
+fn on_tray_left_click(app: &AppHandle) {
+    let action = get_string_setting(app, "trayLeftClickAction", DEFAULT_TRAY_LEFT_CLICK_ACTION);
+    log::info!("🖱️ Tray icon clicked: {}", action);
+    match action.as_str() {
+        "break-now" => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let _ = trigger_overlay(app, None).await;
+            });
+        }
+        "toggle-pause" => {
+            if let Err(e) = toggle_pause(app) {
+                log::error!("❌ Failed to toggle pause: {}", e);
+            }
+        }
+        "show-status" => {
+            let status = tray_tooltip(&app.state::<TimerState>().0.lock().unwrap());
+            if let Err(e) = app
+                .notification()
+                .builder()
+                .title(locale::text(app, "notification.title"))
+                .body(status)
+                .show()
+            {
+                log::error!("❌ Failed to show the status notification: {}", e);
+            }
+        }
+        _ => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let _ = open_settings(app).await;
+            });
+        }
+    }
+}
+
+/// Brings the tray tooltip, pause item label and focus menu in line with the current timer state.
+fn refresh_tray(app: &AppHandle) {
+    let Some(tray) = app.try_state::<TrayState>() else {
+        return;
+    };
+    let timer_state = app.state::<TimerState>();
+    let (paused, focusing, snoozed_for_day, on_break, tooltip) = {
+        let timer = timer_state.0.lock().unwrap();
+        (
+            timer.paused,
+            timer.focus_until.is_some(),
+            timer.snoozed_until_tomorrow,
+            timer.break_active && !timer.preview,
+            tray_tooltip(&timer),
+        )
+    };
+    let icon = if on_break {
+        tray_icon::TrayIconKind::Break
+    } else if paused || focusing || snoozed_for_day {
+        tray_icon::TrayIconKind::Paused
+    } else {
+        tray_icon::TrayIconKind::Running
+    };
+    tray_icon::show(app, icon);
+    let text = if paused { "Resume Nudges" } else { "Pause Nudges" };
+    let _ = tray.pause_item.set_text(text);
+    let _ = tray.icon.set_tooltip(Some(tooltip));
+    let text = if snoozed_for_day {
+        "Snoozed until tomorrow — resume now?"
+    } else {
+        "Snooze Until Tomorrow"
+    };
+    let _ = tray.snooze_day_item.set_text(text);
+
+    let cancel_shown = tray.focus_menu.get(FOCUS_CANCEL_MENU_ID).is_some();
+    if focusing && !cancel_shown {
+        let _ = tray.focus_menu.append(&tray.focus_cancel_item);
+    } else if !focusing && cancel_shown {
+        let _ = tray.focus_menu.remove(&tray.focus_cancel_item);
+    }
+}
+
+
+/// Reads a setting. Values that fail validation are logged and treated as missing, so every
+/// reader falls back to its default instead of acting on garbage.
+fn get_setting(app: &AppHandle, key: &str) -> Option<serde_json::Value> {
+    let value = app.store(SETTINGS_STORE).ok()?.get(key)?;
+    match settings::validate_known_value(key, &value) {
+        Ok(()) => Some(value),
+        Err(e) => {
+            log::warn!("⚠️ Ignoring invalid setting: {}", e);
+            None
+        }
+    }
+}
+
+/// Reads a numeric setting, falling back to `default` if it is missing, invalid or the store is unavailable.
+fn get_u64_setting(app: &AppHandle, key: &str, default: u64) -> u64 {
+    get_setting(app, key).and_then(|v| v.as_u64()).unwrap_or(default)
+}
+
+fn get_f64_setting(app: &AppHandle, key: &str, default: f64) -> f64 {
+    get_setting(app, key).and_then(|v| v.as_f64()).unwrap_or(default)
+}
+
+fn get_string_setting(app: &AppHandle, key: &str, default: &str) -> String {
+    get_setting(app, key)
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| default.to_string())
+}
+
+fn get_bool_setting(app: &AppHandle, key: &str, default: bool) -> bool {
+    get_setting(app, key).and_then(|v| v.as_bool()).unwrap_or(default)
+}
+
+/// Opacity of the overlay backdrop from `overlayOpacity`. Anything below 1.0 needs a
+/// transparent window, which macOS only allows with the private API, so there it is
+/// always fully opaque.
+fn overlay_opacity(app: &AppHandle) -> f64 {
+    if cfg!(target_os = "macos") {
+        return 1.0;
+    }
+    get_f64_setting(app, "overlayOpacity", 1.0)
+}
+
+/// Minutes between micro-breaks today. Falls back to the original `intervalMinutes` setting.
+fn micro_break_interval_minutes(app: &AppHandle) -> u64 {
+    micro_break_interval_minutes_on(&weekday::WeekdaySettings::load(app), today())
+}
+
+/// `micro_break_interval_minutes` on `day`, with that day's `weekdayOverrides`.
+fn micro_break_interval_minutes_on(settings: &weekday::WeekdaySettings, day: chrono::Weekday) -> u64 {
+    settings
+        .first_of(&["microBreakIntervalMinutes", "intervalMinutes"], day)
+        .and_then(|v| v.as_u64())
+        .unwrap_or(20)
+}
+
+fn today() -> chrono::Weekday {
+    chrono::Datelike::weekday(&SystemClock.local())
+}
+
+/// Time between micro-breaks for a cycle starting now, or the length of a work phase in
+/// pomodoro mode. Outside pomodoro mode `frequencyRamp` can shorten it as the day goes on.
+/// With `batterySaverProfile` on, running on battery stretches it by
+/// `batteryIntervalMultiplier` for fewer interruptions; after too many skipped breaks it
+/// shrinks to `escalationIntervalPercent`. See `SchedulerConfig::interval_at`.
+fn break_interval(app: &AppHandle, clock: &dyn Clock) -> Duration {
+    schedule::SchedulerConfig::load(app, clock).interval_at(clock.local())
+}
+
+fn break_duration_seconds(app: &AppHandle, kind: BreakKind) -> u64 {
+    // The end-of-day break keeps its own length in pomodoro mode too.
+    if schedule::pomodoro_mode(app) && kind != BreakKind::EndOfDay {
+        return schedule::break_seconds(app, kind);
+    }
+    let settings = weekday::WeekdaySettings::load(app);
+    match kind {
+        BreakKind::Micro => settings
+            .first_of(&["microBreakDurationSeconds", "overlayDurationSeconds"], today())
+            .and_then(|v| v.as_u64())
+            .unwrap_or(30),
+        BreakKind::Long => settings.u64_on("longBreakDurationSeconds", today(), 300),
+        BreakKind::EndOfDay => get_u64_setting(app, "endOfDayBreakDurationSeconds", 300),
+    }
+}
+
+/// Picks the kind of the break that is about to fire and advances the micro-break counter.
+/// A `long_every` of 0 disables long breaks.
+fn next_break_kind(micro_breaks_since_long: &mut u64, long_every: u64) -> BreakKind {
+    if long_every > 0 && *micro_breaks_since_long >= long_every {
+        *micro_breaks_since_long = 0;
+        BreakKind::Long
+    } else {
+        *micro_breaks_since_long += 1;
+        BreakKind::Micro
+    }
+}
+
+/// Records a due break of the loop `id` that the app held back on its own (fullscreen app,
+/// do-not-disturb), so it isn't mistaken for one the user skipped. A named timer's breaks
+/// are recorded under its name, as when they are shown.
+fn record_auto_postponed(app: &AppHandle, id: &TimerId) {
+    let kind = match id {
+        TimerId::Regular => {
+            let pending = app.state::<TimerState>().0.lock().unwrap().pending_break;
+            pending.unwrap_or(BreakKind::Micro).as_str().to_string()
+        }
+        TimerId::Named(name) => name.clone(),
+    };
+    if let Err(e) = stats::record_break(app, &kind, stats::BreakOutcome::AutoPostponed, Duration::ZERO) {
+        log::error!("❌ Failed to record postponed break: {}", e);
+    }
+}
+
+/// Records the outcome of the break in progress and announces it with `break-finished`, if
+/// that hasn't happened yet.
+fn finish_break(app: &AppHandle, completed: bool) {
+    let (started_at, kind, reset_schedule, break_timer) = {
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        let started_at = timer.break_started_at.take();
+        let reset_schedule = started_at.is_some() && std::mem::take(&mut timer.reset_schedule_after_break);
+        (started_at, timer.current_break, reset_schedule, timer.break_timer.clone())
+    };
+    let Some(started_at) = started_at else {
+        return;
+    };
+    {
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        timer.last_break = Some(LastBreak {
+            kind,
+            reminder: timer.reminder.clone(),
+            message: timer.break_message.clone(),
+            exercise: timer.break_exercise.clone(),
+        });
+        // A skipped break leaves the stretch running; the clock picks up again once the
+        // overlay closes.
+        if completed {
+            timer.reset_work_clock();
+        }
+    }
+    // Skipping an extra timer's reminder or the end-of-day break says nothing about the
+    // regular breaks.
+    if break_timer.is_none() && kind != BreakKind::EndOfDay {
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        timer.consecutive_skips = if completed { 0 } else { timer.consecutive_skips + 1 };
+    }
+    if !completed && escalation_level(app) > 0 {
+        log::info!("📈 Breaks keep being skipped, nudging harder (level {})", escalation_level(app));
+    }
+    if reset_schedule {
+        log::info!("🔄 Manual break over, restarting the schedule");
+        start_timer(app, TimerId::Regular);
+    }
+    sound::play_cue(app, sound::SoundCue::BreakEnd);
+    let elapsed = started_at.elapsed();
+    if let Some(session) = app.try_state::<SessionState>() {
+        let counter = if completed { &session.breaks_completed } else { &session.breaks_skipped };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+    let event = if completed {
+        events::LifecycleEvent::BreakEnded { kind, timer: break_timer, elapsed }
+    } else {
+        events::LifecycleEvent::Skipped { kind, timer: break_timer, elapsed }
+    };
+    events::publish(app, event);
+    let finished = BreakFinished {
+        kind,
+        completed,
+        elapsed_seconds: elapsed.as_secs(),
+    };
+    if let Err(e) = app.emit("break-finished", finished) {
+        log::error!("❌ Failed to emit break-finished: {}", e);
+    }
+    hooks::run_break_end_command(app, kind, completed, elapsed.as_secs());
+}
+
+fn overlay_windows(app: &AppHandle) -> Vec<WebviewWindow> {
+    app.webview_windows()
+        .into_iter()
+        .filter(|(label, _)| label.starts_with(OVERLAY_WINDOW_LABEL_PREFIX))
+        .map(|(_, window)| window)
+        .collect()
+}
+
+/// Closes the overlays once they have played their fade-out. They are told to start it
+/// with `break-fading-out`, and the countdown's close handle then waits `overlayFadeOutMs`
+/// before destroying them, so the usual ways of ending a break still cancel it.
+fn fade_out_overlay_windows(app: &AppHandle) {
+    let fade_out_ms = get_u64_setting(app, "overlayFadeOutMs", 300);
+    if fade_out_ms == 0 || overlay_windows(app).is_empty() {
+        close_overlay_windows(app);
+        return;
+    }
+
+    if let Err(e) = app.emit("break-fading-out", fade_out_ms) {
+        log::error!("❌ Failed to emit break-fading-out: {}", e);
+    }
+    let fade_handle = tauri::async_runtime::spawn({
+        let app = app.clone();
+        async move {
+            tokio::time::sleep(Duration::from_millis(fade_out_ms)).await;
+            // Taken first so closing the windows doesn't abort this very task.
+            app.state::<TimerState>().0.lock().unwrap().close_handle.take();
+            close_overlay_windows(&app);
+        }
+    });
+    let timer_state = app.state::<TimerState>();
+    let mut timer = timer_state.0.lock().unwrap();
+    if let Some(old) = timer.close_handle.replace(fade_handle) {
+        old.abort();
+    }
+}
+
+/// Destroys every overlay window straight away, skipping any fade-out, so a break always
+/// ends on all monitors at once, then emits `break-ended`.
+fn close_overlay_windows(app: &AppHandle) {
+    {
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        if let Some(handle) = timer.close_handle.take() {
+            handle.abort();
+        }
+        timer.break_active = false;
+        timer.preview = false;
+        timer.escalated_strict = false;
+        timer.resume_work_clock();
+    }
+    refresh_tray(app);
+    let windows = overlay_windows(app);
+    if windows.is_empty() {
+        return;
+    }
+    for window in windows {
+        if let Err(e) = window.destroy() {
+            log::error!("❌ Failed to close overlay window '{}': {}", window.label(), e);
+            // Don't leave an unclosable window pinned over everything.
+            let _ = window.set_always_on_top(false);
+            let _ = window.hide();
+        }
+    }
+    if let Err(e) = app.emit("break-ended", ()) {
+        log::error!("❌ Failed to emit break-ended: {}", e);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn is_wayland_session() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+        || std::env::var("XDG_SESSION_TYPE").is_ok_and(|session| session == "wayland")
+}
+
+fn keep_overlay_on_top(window: &WebviewWindow) {
+    if let Err(e) = window.set_always_on_top(true) {
+        log::warn!("⚠️ Could not keep overlay '{}' on top: {}", window.label(), e);
+    }
+}
+
+/// Some Linux window managers (and most Wayland compositors) drop a fullscreen or keep-above
+/// request made before the window is mapped, so both are asked for again once it exists.
+#[cfg(target_os = "linux")]
+fn ensure_overlay_covers_screen(window: &WebviewWindow, always_on_top: bool) {
+    if always_on_top {
+        keep_overlay_on_top(window);
+    }
+    if !window.is_fullscreen().unwrap_or(false) {
+        log::info!("🐧 Overlay '{}' isn't fullscreen yet, asking again", window.label());
+        if let Err(e) = window.set_fullscreen(true) {
+            log::warn!("⚠️ Could not make overlay '{}' fullscreen: {}", window.label(), e);
+        }
+    }
+}
+
+/// Starts a break of `kind` on every monitor. If a break is already on screen it is brought
+/// to the front instead and `false` is returned, so there is never more than one.
+async fn show_overlay_window(app: &AppHandle, kind: BreakKind) -> bool {
+    open_overlays(app, kind, false, None).await
+}
+
+/// Builds one overlay window, retrying up to `overlayCreateRetries` times with a growing
+/// pause in between. Every failed attempt is logged; `None` means they all failed.
+async fn build_overlay_window(
+    app: &AppHandle,
+    label: &str,
+    url: &WebviewUrl,
+    monitor: Option<&tauri::Monitor>,
+    mode: appearance::OverlayMode,
+    always_on_top: bool,
+) -> Option<WebviewWindow> {
+    let fullscreen = mode == appearance::OverlayMode::Fullscreen;
+    let retries = get_u64_setting(app, "overlayCreateRetries", 2);
+    let mut attempt = 0;
+    loop {
+        attempt += 1;
+        let mut builder = WebviewWindowBuilder::new(app, label, url.clone())
+            .decorations(false)
+            .skip_taskbar(true)
+            .always_on_top(always_on_top);
+        #[cfg(not(target_os = "macos"))]
+        {
+            builder = builder.transparent(overlay_opacity(app) < 1.0);
+        }
+        #[cfg(target_os = "windows")]
+        if appearance::overlay_blur(app) {
+            builder = builder.transparent(true).effects(
+                tauri::window::EffectsBuilder::new()
+                    .effect(tauri::window::Effect::Acrylic)
+                    .build(),
+            );
+        }
+        // Wayland compositors ignore the requested position and often the early fullscreen
+        // request too; a maximized window at least covers the output it lands on.
+        #[cfg(target_os = "linux")]
+        if fullscreen && is_wayland_session() {
+            builder = builder.maximized(true);
+        }
+        let size = appearance::overlay_size(app, mode, monitor);
+        builder = match monitor {
+            Some(monitor) => {
+                let position = appearance::overlay_position(mode, monitor, size);
+                builder.position(position.x, position.y)
+            }
+            None => builder.center(),
+        };
+        // The corner toast sits beside the user's work rather than interrupting it.
+        builder = builder
+            .inner_size(size.width, size.height)
+            .focused(mode != appearance::OverlayMode::CornerToast);
 
-#[tauri::command]
-async fn trigger_overlay(app: AppHandle) -> Result<(), String> {
-    println!("✅ RUST: trigger_overlay command received!");
-    show_overlay_window(&app).await;
-    Ok(())
+        match builder.fullscreen(fullscreen).build() {
+            Ok(window) => return Some(window),
+            Err(e) => {
+                log::error!(
+                    "❌ Failed to create overlay window '{}' (attempt {}/{}): {}",
+                    label,
+                    attempt,
+                    retries + 1,
+                    e
+                );
+                if attempt > retries {
+                    return None;
+                }
+                tokio::time::sleep(OVERLAY_RETRY_BACKOFF * attempt as u32).await;
+            }
+        }
+    }
 }
 
-#[tauri::command]
-async fn get_overlay_duration(app: AppHandle) -> Result<u64, String> {
-    println!("✅ RUST: get_overlay_duration command received!");
-    let store = app.store("settings.json").map_err(|e| e.to_string())?;
-    let duration = store
-        .get("overlayDurationSeconds")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(30);
-    println!("✅ RUST: Returning duration: {} seconds", duration);
-    Ok(duration)
+/// Shows the overlays for a real break or, with `preview`, a short preview of them that
+/// leaves no trace: nothing is recorded, announced or played, and the schedule carries on.
+/// A break from one of the extra `timers` takes its length and text from `from_timer`.
+async fn open_overlays(app: &AppHandle, kind: BreakKind, preview: bool, from_timer: Option<&timers::TimerConfig>) -> bool {
+    open_overlays_with(app, kind, preview, false, from_timer).await
 }
 
-#[tauri::command]
-async fn open_settings(app: AppHandle) -> Result<(), String> {
-    println!("✅ RUST: open_settings command received!");
-    if let Some(window) = app.get_webview_window(SETTINGS_WINDOW_LABEL) {
-        println!("✅ RUST: Settings window exists, showing it");
-        let _ = window.show();
-        let _ = window.set_focus();
+/// `open_overlays`, or with `replay` a preview showing what the last break showed.
+async fn open_overlays_with(
+    app: &AppHandle,
+    kind: BreakKind,
+    preview: bool,
+    replay: bool,
+    from_timer: Option<&timers::TimerConfig>,
+) -> bool {
+    log::info!("🎬 Opening overlays ({:?} break{})", kind, if replay { ", replay" } else if preview { ", preview" } else { "" });
+    let duration_seconds = if preview {
+        PREVIEW_DURATION_SECONDS
+    } else if let Some(config) = from_timer {
+        config.duration_seconds
     } else {
-        println!("✅ RUST: Creating new settings window");
-        let _ = WebviewWindowBuilder::new(&app, SETTINGS_WINDOW_LABEL, WebviewUrl::App("settings.html".into()))
-            .title("Nudge Settings")
-            .inner_size(600.0, 700.0)
-            .resizable(false)
-            .center()
-            .build();
+        break_duration_seconds(app, kind)
+    };
+    let escalate = !preview && escalation_level(app) > 0 && get_bool_setting(app, "escalationStrict", true);
+    {
+        // Claimed under the lock before any window exists, so a manual trigger and the
+        // timer loop can't both get past this point.
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        if !timer.claim_screen() {
+            drop(timer);
+            log::info!("✅ A break is already showing, bringing it to the front");
+            for window in overlay_windows(app) {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+            return false;
+        }
+        timer.preview = preview;
+        if !preview && from_timer.is_none() {
+            timer.interval_extension_minutes = 0;
+        }
+        timer.break_timer = from_timer.map(|config| config.name.clone());
+        timer.escalated_strict = escalate;
+        timer.current_break = kind;
+        timer.break_duration_seconds = duration_seconds;
+        timer.break_remaining_seconds = duration_seconds;
+        timer.replaying = replay;
+        match timer.last_break.clone().filter(|_| replay) {
+            Some(last) => {
+                timer.break_message = last.message;
+                timer.break_exercise = last.exercise;
+            }
+            None => {
+                timer.break_message = None;
+                timer.break_exercise = None;
+            }
+        }
+        timer.confirmations_received = 0;
+        timer.focused_overlays.clear();
+        timer.overlay_lost_focus = false;
+        timer.content_overlay = None;
+        let worked_seconds = timer.worked().as_secs();
+        timer.break_worked_seconds = (!preview).then_some(worked_seconds);
+        if !preview {
+            timer.suspend_work_clock();
+        }
     }
-    Ok(())
+    // Long breaks suggest an exercise instead.
+    let last_reminder = app.state::<TimerState>().0.lock().unwrap().last_break.as_ref().map(|last| last.reminder.clone());
+    let reminder = match (from_timer, kind) {
+        _ if replay => last_reminder.flatten(),
+        (Some(config), _) => Some(config.reminder()),
+        (None, BreakKind::Micro) => reminders::pick_reminder(app, !preview),
+        (None, BreakKind::Long) => None,
+        (None, BreakKind::EndOfDay) => Some(end_of_day::reminder(app)),
+    };
+    if let Some(reminder) = &reminder {
+        log::info!("💧 Break reminder: {}", reminder.name);
+    }
+    app.state::<TimerState>().0.lock().unwrap().reminder = reminder;
+
+    let monitors = monitors::overlay_monitors(app);
+    log::info!("🔨 Creating overlay windows for {} monitor(s)", monitors.len());
+
+    let always_on_top = get_bool_setting(app, "overlayAlwaysOnTop", true);
+    let mode = appearance::overlay_mode(app);
+    let url = remote_overlay::overlay_url(app, kind).await;
+    let primary_only = get_bool_setting(app, "primaryContentOnly", false) && monitors.len() > 1;
+    let mut created = 0;
+    let mut targets: Vec<Option<&tauri::Monitor>> = if monitors.is_empty() {
+        vec![None]
+    } else {
+        monitors.iter().map(Some).collect()
+    };
+    if primary_only {
+        // The active monitor goes first so it gets the content; should its window fail,
+        // the next one to open takes over.
+        targets.swap(0, monitors::active_monitor_index(app, &monitors));
+    }
+    for (index, monitor) in targets.into_iter().enumerate() {
+        let label = format!("{}{}", OVERLAY_WINDOW_LABEL_PREFIX, index);
+        let Some(window) = build_overlay_window(app, &label, &url, monitor, mode, always_on_top).await else {
+            continue;
+        };
+        log::info!("✅ Overlay window '{}' created successfully", label);
+        created += 1;
+        if primary_only {
+            let timer_state = app.state::<TimerState>();
+            let mut timer = timer_state.0.lock().unwrap();
+            if timer.content_overlay.is_none() {
+                log::info!("🖥️ Break content on '{}', dimming the other monitors", label);
+                timer.content_overlay = Some(label.clone());
+            }
+        }
+        #[cfg(target_os = "linux")]
+        if mode == appearance::OverlayMode::Fullscreen {
+            ensure_overlay_covers_screen(&window, always_on_top);
+        } else if always_on_top {
+            keep_overlay_on_top(&window);
+        }
+
+        let app = app.clone();
+        let label = label.clone();
+        let overlay = window.clone();
+        window.on_window_event(move |event| {
+            match event {
+                tauri::WindowEvent::Focused(focused) => {
+                    // Some compositors drop keep-above when focus changes.
+                    if always_on_top {
+                        keep_overlay_on_top(&overlay);
+                    }
+                    let timer_state = app.state::<TimerState>();
+                    let mut timer = timer_state.0.lock().unwrap();
+                    if *focused {
+                        timer.focused_overlays.insert(label.clone());
+                        timer.overlay_lost_focus = false;
+                    } else {
+                        timer.focused_overlays.remove(&label);
+                        timer.overlay_lost_focus = timer.focused_overlays.is_empty();
+                    }
+                }
+                tauri::WindowEvent::CloseRequested { api, .. } => {
+                    log::info!("🚪 Overlay window close requested");
+                    if strict_mode(&app) && !app.state::<TimerState>().0.lock().unwrap().preview {
+                        log::warn!("🔒 Strict mode: keeping the overlay open");
+                        api.prevent_close();
+                    }
+                }
+                tauri::WindowEvent::Destroyed => {
+                    log::info!("💥 Overlay window destroyed");
+                    // A countdown that ran out has already finished the break, so this is
+                    // the overlay being closed from outside the app: a skip.
+                    finish_break(&app, false);
+                    close_overlay_windows(&app);
+                }
+                _ => {}
+            }
+        });
+    }
+
+    if created == 0 {
+        {
+            let timer_state = app.state::<TimerState>();
+            let mut timer = timer_state.0.lock().unwrap();
+            timer.break_active = false;
+            timer.resume_work_clock();
+        }
+        if !preview {
+            show_break_fallback_notification(app);
+        }
+        return false;
+    }
+    let content_overlay = app.state::<TimerState>().0.lock().unwrap().content_overlay.clone();
+    if let Some(window) = content_overlay.and_then(|label| app.get_webview_window(&label)) {
+        let _ = window.set_focus();
+    }
+    refresh_tray(app);
+    if !preview {
+        tray_icon::start_break_animation(app);
+        if get_bool_setting(app, "accessibilityMode", false) {
+            announce_break(app, kind);
+        }
+    }
+
+    let close_handle = tauri::async_runtime::spawn({
+        let app = app.clone();
+        async move {
+            run_break_countdown(&app).await;
+            log::info!("⏲️ Break over, closing the overlay");
+            finish_break(&app, true);
+            fade_out_overlay_windows(&app);
+        }
+    });
+
+    let timer_state = app.state::<TimerState>();
+    let mut timer = timer_state.0.lock().unwrap();
+    if let Some(old) = timer.close_handle.replace(close_handle) {
+        old.abort();
+    }
+    if preview {
+        // Without a start time finish_break has nothing to record for it.
+        return true;
+    }
+    timer.break_started_at = Some(Instant::now());
+    drop(timer);
+    sound::play_cue(app, sound::SoundCue::BreakStart);
+    if let Err(e) = app.emit("break-started", BreakStarted { kind, duration_seconds }) {
+        log::error!("❌ Failed to emit break-started: {}", e);
+    }
+    events::publish(app, events::LifecycleEvent::BreakShown { kind, duration_seconds });
+    true
 }
 
-#[tauri::command]
-fn exit_app(app: AppHandle) {
-    println!("✅ RUST: exit_app command received!");
-    app.exit(0);
+/// Emits `break-tick` with the seconds left once a second, from the full duration down to
+/// zero. The overlay only renders these, so its countdown can't drift from the backend's.
+/// Both numbers are read from the timer on every tick, so `extend_break` can move them.
+///
+/// With `pauseDurationWhenUnfocused`, seconds spent with focus away from every overlay
+/// window don't count, so the break lasts until its full duration was spent on it.
+async fn run_break_countdown(app: &AppHandle) {
+    let minimum_seconds = minimum_break_seconds(app);
+    let mut ticks = tokio::time::interval(Duration::from_secs(1));
+    ticks.tick().await;
+    loop {
+        let tick = {
+            let timer_state = app.state::<TimerState>();
+            let timer = timer_state.0.lock().unwrap();
+            BreakTick {
+                remaining_seconds: timer.break_remaining_seconds,
+                duration_seconds: timer.break_duration_seconds,
+                grace_remaining_seconds: minimum_break_remaining(timer.break_started_at, minimum_seconds),
+            }
+        };
+        let remaining_seconds = tick.remaining_seconds;
+        emit_break_tick(app, tick);
+        if remaining_seconds == 0 {
+            return;
+        }
+
+        ticks.tick().await;
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        if timer.overlay_lost_focus && get_bool_setting(app, "pauseDurationWhenUnfocused", false) {
+            continue;
+        }
+        timer.break_remaining_seconds = timer.break_remaining_seconds.saturating_sub(1);
+    }
 }
 
-#[tauri::command]
-fn restart_timer(app: AppHandle) {
-    println!("✅ RUST: Restarting timer due to settings change.");
-    start_break_timer(app);
+fn emit_break_tick(app: &AppHandle, tick: BreakTick) {
+    if let Err(e) = app.emit("break-tick", tick) {
+        log::error!("❌ Failed to emit break-tick: {}", e);
+    }
 }
 
+/// (Re)starts the break loop `id` from the beginning of a cycle. It stays stopped while
+/// paused or in focus mode, with an interval of 0, and for a named timer that is no longer
+/// in `timers`.
+fn start_timer(app: &AppHandle, id: TimerId) {
+    log::info!("⏰ Starting {}", id);
+    {
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        if let Some(running) = timer.loops.get(&id) {
+            log::info!("🛑 Stopping existing timer");
+            running.timer_loop.cancel.cancel();
+        }
+        if id == TimerId::Regular {
+            timer.snoozes_this_break = 0;
+            timer.postponements_this_break = 0;
+            timer.interval_extension_minutes = 0;
+            timer.pending_break = None;
+        }
 
-async fn show_overlay_window(app: &AppHandle) {
-    println!("🎬 RUST: show_overlay_window called");
-    
-    if let Some(window) = app.get_webview_window(OVERLAY_WINDOW_LABEL) {
-        println!("✅ RUST: Overlay window exists, showing it");
-        let _ = window.show();
-        let _ = window.set_focus();
-        return;
+        if timer.paused {
+            log::info!("⏸️ Timer is paused, not starting timer");
+            timer.stop_timer_loop(&id);
+            drop(timer);
+            refresh_tray(app);
+            return;
+        }
+        if timer.focus_until.is_some() {
+            log::info!("🎧 Focus mode is on, not starting timer");
+            timer.stop_timer_loop(&id);
+            drop(timer);
+            refresh_tray(app);
+            return;
+        }
     }
 
-    println!("🔨 RUST: Creating new overlay window");
-    match WebviewWindowBuilder::new(
-        app,
-        OVERLAY_WINDOW_LABEL,
-        WebviewUrl::App("index.html".into()),
-    )
-    .fullscreen(true)
-    .decorations(false)
-    .skip_taskbar(true)
-    .center()
-    .build()
+    let Some((config, _)) = timer_schedule(app, &id, &SystemClock) else {
+        log::info!("🗑️ {} is no longer configured, stopping it", id);
+        app.state::<TimerState>().0.lock().unwrap().stop_timer_loop(&id);
+        return;
+    };
+    start_timer_with(app, id, &config, &SystemClock);
+}
+
+/// Starts the named `timers`, stopping the loops of any that have been removed.
+fn start_named_timers(app: &AppHandle) {
+    let names: Vec<String> = timers::load_timers(app).into_iter().map(|entry| entry.name).collect();
     {
-        Ok(window) => {
-            println!("✅ RUST: Overlay window created successfully");
-            
-            window.on_window_event(move |event| {
-                match event {
-                    tauri::WindowEvent::CloseRequested { .. } => {
-                        println!("🚪 RUST: Overlay window close requested");
-                    }
-                    tauri::WindowEvent::Destroyed => {
-                        println!("💥 RUST: Overlay window destroyed");
-                    }
-                    _ => {}
-                }
-            });
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        let removed: Vec<TimerId> = timer
+            .loops
+            .keys()
+            .filter(|id| matches!(id, TimerId::Named(name) if !names.contains(name)))
+            .cloned()
+            .collect();
+        for id in removed {
+            timer.stop_timer_loop(&id);
         }
-        Err(e) => {
-            println!("❌ RUST: Failed to create overlay window: {}", e);
+    }
+    for name in names {
+        start_timer(app, TimerId::Named(name));
+    }
+}
+
+/// Starts the regular schedule and every named timer afresh.
+fn start_all_timers(app: &AppHandle) {
+    start_timer(app, TimerId::Regular);
+    start_named_timers(app);
+}
+
+/// What the loop `id` runs on: its schedule and, for a named timer, its entry of `timers`.
+/// `None` for a named timer that is no longer configured.
+fn timer_schedule(
+    app: &AppHandle,
+    id: &TimerId,
+    clock: &dyn Clock,
+) -> Option<(schedule::SchedulerConfig, Option<timers::TimerConfig>)> {
+    match id {
+        TimerId::Regular => Some((schedule::SchedulerConfig::load(app, clock), None)),
+        TimerId::Named(name) => {
+            let entry = timers::load_timers(app).into_iter().find(|entry| entry.name == *name)?;
+            Some((schedule::SchedulerConfig::for_timer(app, clock, entry.interval_minutes), Some(entry)))
         }
     }
 }
 
-fn start_break_timer(app: AppHandle) {
-    println!("⏰ RUST: start_break_timer called");
-    let timer_state = app.state::<TimerState>();
+/// Starts the loop `id` from an already gathered `config`, reading the time from `clock`.
+fn start_timer_with(app: &AppHandle, id: TimerId, config: &schedule::SchedulerConfig, clock: &'static dyn Clock) {
+    log::info!("⏰ {} started with interval: {} minutes", id, config.interval_minutes);
+
+    let Some(first_delay) = config.first_delay(clock) else {
+        log::warn!("⚠️ Timer interval is 0, not starting timer");
+        app.state::<TimerState>().0.lock().unwrap().stop_timer_loop(&id);
+        refresh_tray(app);
+        return;
+    };
+
+    let interval = config.interval_at(clock.local());
+    if interval.as_secs() != config.interval_minutes * 60 {
+        log::info!("🔋 Breaks adjusted to every {:?}", interval);
+    }
+    // A snooze or postponement from before a restart still decides when the next break is due.
+    if let Some(remaining) = config.snooze_remaining(clock) {
+        log::info!("😴 Carrying on a snooze, break due in {:?}", remaining);
+    }
+    if config.startup_grace.is_some_and(|grace| grace == first_delay) {
+        log::info!("🌱 Just launched, holding the first break back for {:?}", first_delay);
+    }
+    spawn_timer_loop(app, id, first_delay, clock);
+}
+
+/// What is left of `startupGraceMinutes`, the stretch after launch in which no break fires.
+/// `None` once it has passed or when it is 0.
+fn startup_grace_remaining(app: &AppHandle) -> Option<Duration> {
+    let grace = Duration::from_secs(get_u64_setting(app, "startupGraceMinutes", 0) * 60);
+    let since_launch = app.try_state::<SessionState>()?.started_at.elapsed();
+    grace.checked_sub(since_launch).filter(|remaining| !remaining.is_zero())
+}
+
+/// Sleeps until the next break is due, sending the pre-break warning on the way if enabled.
+async fn wait_for_break(app: &AppHandle, delay: Duration) {
+    let warning = Duration::from_secs(get_u64_setting(app, "preBreakWarningSeconds", 30));
+    if warning.is_zero() || delay <= warning {
+        tokio::time::sleep(delay).await;
+        return;
+    }
 
-    if let Some(handle) = timer_state.0.lock().unwrap().take() {
-        println!("🛑 RUST: Stopping existing timer");
-        handle.abort();
+    tokio::time::sleep(delay - warning).await;
+    if !overlay_windows(app).is_empty() {
+        // A manually triggered break is already on screen, so there's nothing to warn about.
+        log::warn!("⚠️ Break already showing, skipping pre-break warning");
+    } else if in_dnd_window(app) {
+        log::info!("🔕 Inside a do-not-disturb window, skipping pre-break warning");
+    } else {
+        show_pre_break_warning(app, warning.as_secs());
     }
+    tokio::time::sleep(warning).await;
+}
 
-    let store = app.store("settings.json").expect("Failed to get store");
+/// Holds a due break back while a fullscreen app is in the foreground, checking again every
+/// `fullscreenRetrySeconds` up to `fullscreenMaxRetries` times. Returns false if it gave up.
+async fn wait_for_fullscreen_to_end(app: &AppHandle) -> bool {
+    if !get_bool_setting(app, "skipDuringFullscreen", false) {
+        return true;
+    }
 
-    let interval_minutes = store
-        .get("intervalMinutes")
-        .and_then(|v| v.as_u64())
-        .unwrap_or(20);
-    
-    println!("⏰ RUST: Timer started with interval: {} minutes", interval_minutes);
+    let retry_delay = Duration::from_secs(get_u64_setting(app, "fullscreenRetrySeconds", 60));
+    let max_retries = get_u64_setting(app, "fullscreenMaxRetries", 5);
+    let mut retries = 0;
+    while fullscreen::foreground_app_is_fullscreen() {
+        if retries >= max_retries {
+            return false;
+        }
+        retries += 1;
+        log::info!("🎮 Fullscreen app detected, postponing break (retry {}/{})", retries, max_retries);
+        tokio::time::sleep(retry_delay).await;
+    }
+    true
+}
+
+/// With `waitForIdleToShow` on, holds a due break back until the user has stopped typing or
+/// moving the mouse for `idleToShowSeconds`, or until `maxDeferSeconds` have gone by.
+async fn wait_for_idle_moment(app: &AppHandle) {
+    // Without idle detection the user always looks busy, so this would only ever add delay.
+    if !get_bool_setting(app, "waitForIdleToShow", false) || !idle::SUPPORTED {
+        return;
+    }
 
-    if interval_minutes == 0 { 
-        println!("⚠️ RUST: Timer interval is 0, not starting timer");
-        return; 
+    let threshold = get_u64_setting(app, "idleToShowSeconds", 3);
+    let deadline = Instant::now() + Duration::from_secs(get_u64_setting(app, "maxDeferSeconds", 60));
+    loop {
+        let idle_seconds = idle::system_idle_seconds();
+        if idle_seconds >= threshold {
+            return;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            log::info!("⌨️ Still busy after the longest deferral, showing the break anyway");
+            return;
+        }
+        // Any input restarts the idle count, so the soonest the threshold can be reached is
+        // in the time it still lacks; there's no point looking again before then.
+        let wait = Duration::from_secs(threshold - idle_seconds).min(deadline - now);
+        log::debug!("⌨️ User is active, deferring break for {:?}", wait);
+        tokio::time::sleep(wait).await;
     }
+}
 
-    let new_handle = tauri::async_runtime::spawn({
+/// Keeps the app controllable when the desktop has no system tray: opens the settings
+/// window, which then offers pause and quit, and says how to get back to it later.
+fn run_without_tray(app: &AppHandle) {
+    tauri::async_runtime::spawn({
         let app = app.clone();
         async move {
-            let mut interval = interval(Duration::from_secs(interval_minutes * 60));
-            println!("⏱️ RUST: Timer loop started, waiting {} minutes", interval_minutes);
-            loop {
-                interval.tick().await;
-                println!("⏰ RUST: Timer ticked! Showing overlay window");
-                show_overlay_window(&app).await;
-            }
+            let _ = open_settings(app).await;
         }
     });
-    
-    *timer_state.0.lock().unwrap() = Some(new_handle);
-    println!("✅ RUST: Timer handle stored in state");
+    let hotkey = get_string_setting(app, "settingsHotkey", hotkeys::DEFAULT_SETTINGS_HOTKEY);
+    let body = if hotkey.is_empty() {
+        "Nudge is running without a tray icon.".to_string()
+    } else {
+        format!("Nudge is running without a tray icon. Press {} to open its settings.", hotkey)
+    };
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(locale::text(app, "notification.title"))
+        .body(body)
+        .show()
+    {
+        log::error!("❌ Failed to show the no-tray notification: {}", e);
+    }
+}
+
+/// The first time the main window is closed, says the app is still running in the tray, so
+/// it isn't mistaken for having quit. `shownTrayHint` keeps it from coming up again.
+fn show_tray_hint_once(app: &AppHandle) {
+    if app.try_state::<TrayState>().is_none() || get_bool_setting(app, "shownTrayHint", false) {
+        return;
+    }
+    log::info!("💡 Telling the user the app is still in the tray");
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(locale::text(app, "notification.title"))
+        .body(locale::text(app, "notification.trayHint"))
+        .show()
+    {
+        log::error!("❌ Failed to show the tray hint: {}", e);
+        return;
+    }
+    match app.store(SETTINGS_STORE) {
+        Ok(store) => {
+            store.set("shownTrayHint", serde_json::json!(true));
+            if let Err(e) = store.save() {
+                log::error!("❌ Failed to save shownTrayHint: {}", e);
+            }
+        }
+        Err(e) => log::error!("❌ Failed to open the settings store: {}", e),
+    }
+}
+
+/// Whether the tray icon exists. Without it the settings window shows its own pause and
+/// quit controls.
+#[tauri::command]
+fn is_tray_available(app: AppHandle) -> bool {
+    log::debug!("🧭 is_tray_available command received!");
+    app.try_state::<TrayState>().is_some()
+}
+
+/// Stands in for a break whose overlay couldn't be opened, so the nudge isn't lost entirely.
+fn show_break_fallback_notification(app: &AppHandle) {
+    log::warn!("⚠️ No overlay could be opened, sending a notification instead");
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(locale::text(app, "notification.title"))
+        .body(locale::text(app, "notification.breakFallback"))
+        .show()
+    {
+        log::error!("❌ Failed to show the break notification: {}", e);
+    }
+}
+
+/// Repeats the break's heading and text as a notification, which screen readers announce
+/// reliably where a fullscreen webview may not be read at all (`accessibilityMode`).
+fn announce_break(app: &AppHandle, kind: BreakKind) {
+    let reminder = app.state::<TimerState>().0.lock().unwrap().reminder.clone();
+    let (title, body) = match (reminder, kind) {
+        (Some(reminder), _) => (reminder.name, reminder.message),
+        (None, BreakKind::Micro) => (
+            locale::text(app, "break.micro.title"),
+            // The overlay shows the same message: it is picked once per break.
+            messages::get_break_message(app.clone(), None).unwrap_or_else(|_| locale::text(app, "break.micro.message")),
+        ),
+        (None, BreakKind::Long | BreakKind::EndOfDay) => {
+            (locale::text(app, "break.long.title"), locale::text(app, "break.long.message"))
+        }
+    };
+    log::info!("🔊 Announcing the break for screen readers");
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::error!("❌ Failed to announce the break: {}", e);
+    }
+}
+
+fn show_pre_break_warning(app: &AppHandle, seconds: u64) {
+    log::info!("🔔 Sending pre-break warning ({}s)", seconds);
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(locale::text(app, "notification.title"))
+        .body(locale::text(app, "notification.preBreak").replace("{seconds}", &seconds.to_string()))
+        .show()
+    {
+        log::error!("❌ Failed to show pre-break warning: {}", e);
+    }
+}
+
+/// Spawns the break loop `id`, firing first after `first_delay` and then every interval,
+/// which is worked out afresh each cycle from `clock`'s time so a change of power source
+/// takes effect. It takes over from any loop `id` already running (see `TimerLoop::replace`).
+fn spawn_timer_loop(app: &AppHandle, id: TimerId, first_delay: Duration, clock: &'static dyn Clock) {
+    let timer_state = app.state::<TimerState>();
+    let mut timer = timer_state.0.lock().unwrap();
+    if id == TimerId::Regular {
+        timer.phase = None;
+    }
+
+    let run = {
+        let app = app.clone();
+        let id = id.clone();
+        async move {
+            if id == TimerId::Regular && schedule::pomodoro_mode(&app) {
+                schedule::run(app, first_delay, clock).await;
+            } else {
+                run_timer_loop(app, id, first_delay, clock).await;
+            }
+        }
+    };
+    let previous = timer.loops.remove(&id).map(|running| running.timer_loop);
+    let running = ScheduledLoop {
+        timer_loop: TimerLoop::replace(previous, run),
+        deadline: Instant::now() + first_delay,
+        next_break_at: None,
+    };
+    timer.loops.insert(id, running);
+    drop(timer);
+    log::info!("✅ Timer handle stored in state");
+    refresh_tray(app);
+}
+
+/// The loop behind every timer: the regular schedule outside pomodoro mode, and each of the
+/// named `timers`. Idle time, work hours, do-not-disturb, the daily quota and fullscreen apps
+/// hold back their breaks alike.
+async fn run_timer_loop(app: AppHandle, id: TimerId, first_delay: Duration, clock: &'static dyn Clock) {
+    log::info!("⏱️ {} loop started, first break in {:?}", id, first_delay);
+    let regular = id == TimerId::Regular;
+    let mut delay = Some(first_delay);
+    let mut first_tick = true;
+    loop {
+        let Some((config, from_timer)) = timer_schedule(&app, &id, clock) else {
+            log::info!("🗑️ {} is no longer configured, stopping it", id);
+            return;
+        };
+        let now = clock.local();
+        let left_today = breaks_left_today(&app);
+        if config.rules.work_start_after(now).is_some() {
+            log::info!("🌙 Outside work hours, waiting for them to begin");
+        } else if left_today == Some(0) {
+            log::info!("🎯 Daily break quota reached, waiting for midnight");
+        }
+        let wait = config.time_to_next_break(clock, delay.take(), left_today);
+        app.state::<TimerState>().0.lock().unwrap().set_deadline(&id, Instant::now() + wait);
+        refresh_tray(&app);
+        let interval = config.interval_at(now);
+
+        if regular {
+            wait_for_break(&app, wait).await;
+        } else {
+            tokio::time::sleep(wait).await;
+        }
+        if regular && first_tick {
+            persist_deadline(&app, "snoozedUntil", None);
+        }
+        log::info!("⏰ {} ticked! Showing overlay window", id);
+        {
+            let timer_state = app.state::<TimerState>();
+            let mut timer = timer_state.0.lock().unwrap();
+            timer.set_deadline(&id, Instant::now() + interval);
+            // The first tick may be a snoozed, postponed or resumed break; later ticks
+            // start new cycles.
+            if regular && !first_tick {
+                timer.snoozes_this_break = 0;
+                timer.postponements_this_break = 0;
+                timer.pending_break = None;
+            }
+        }
+        first_tick = false;
+        if regular {
+            events::publish(&app, events::LifecycleEvent::BreakDue);
+        }
+
+        let idle_threshold = get_u64_setting(&app, "idleResetThresholdSeconds", 300);
+        let idle_seconds = idle::system_idle_seconds();
+        if idle_threshold > 0 && idle_seconds >= idle_threshold {
+            log::info!("💤 User idle for {}s, skipping break and resetting interval", idle_seconds);
+            if regular {
+                app.state::<TimerState>().0.lock().unwrap().reset_work_clock();
+            }
+            continue;
+        }
+        match schedule::ScheduleRules::load(&app).blocked_at(clock.local()) {
+            Some(schedule::Blocked::OutsideWorkHours) => {
+                log::info!("🌙 Work hours are over, skipping break");
+                continue;
+            }
+            Some(schedule::Blocked::DoNotDisturb) => {
+                log::info!("🔕 Inside a do-not-disturb window, skipping break and resetting interval");
+                record_auto_postponed(&app, &id);
+                continue;
+            }
+            Some(schedule::Blocked::DayOver) => {
+                log::info!("🌇 The workday is over, skipping break");
+                continue;
+            }
+            None => {}
+        }
+        if breaks_left_today(&app) == Some(0) {
+            log::info!("🎯 Daily break quota reached, skipping break");
+            continue;
+        }
+        if !wait_for_fullscreen_to_end(&app).await {
+            log::info!("🎮 Fullscreen app still active, giving up on this break");
+            record_auto_postponed(&app, &id);
+            continue;
+        }
+        wait_for_idle_moment(&app).await;
+        app.state::<TimerState>().0.lock().unwrap().set_deadline(&id, Instant::now() + interval);
+        // A preview gives way to the real thing.
+        close_preview(&app);
+
+        if let Some(entry) = from_timer {
+            // Any other break on screen goes first; this one follows it.
+            timers::wait_for_screen(&app).await;
+            open_overlays(&app, BreakKind::Micro, false, Some(&entry)).await;
+            continue;
+        }
+        // A named timer's break on screen is waited out.
+        timers::wait_for_timer_break(&app).await;
+        if app.state::<TimerState>().0.lock().unwrap().break_active {
+            log::info!("🎬 A break is already showing, skipping this one");
+            continue;
+        }
+
+        let long_every = schedule::long_break_every(&app);
+        let kind = {
+            let timer_state = app.state::<TimerState>();
+            let mut timer = timer_state.0.lock().unwrap();
+            // A break pushed back after it started comes back as the same kind it was.
+            match timer.pending_break.take() {
+                Some(kind) => kind,
+                None => next_break_kind(&mut timer.micro_breaks_since_long, long_every),
+            }
+        };
+        show_overlay_window(&app, kind).await;
+    }
+}
+
+/// Logs to stdout and to a file in the app's log directory. The file is named after the day
+/// the app started and rolls over once it reaches `LOG_MAX_FILE_SIZE`, keeping the newest
+/// `LOG_FILES_KEPT` files.
+fn log_plugin() -> tauri::plugin::TauriPlugin<Wry> {
+    use tauri_plugin_log::{RotationStrategy, Target, TargetKind, TimezoneStrategy};
+
+    let file_name = format!("nudge-{}", chrono::Local::now().format("%Y-%m-%d"));
+    tauri_plugin_log::Builder::new()
+        .targets([
+            Target::new(TargetKind::Stdout),
+            Target::new(TargetKind::LogDir { file_name: Some(file_name) }),
+        ])
+        .level(log::LevelFilter::Info)
+        .level_for("nudge", log::LevelFilter::Debug)
+        .timezone_strategy(TimezoneStrategy::UseLocal)
+        .max_file_size(LOG_MAX_FILE_SIZE)
+        .rotation_strategy(RotationStrategy::KeepSome(LOG_FILES_KEPT))
+        .build()
 }
 
 // == Main Application Setup ==
 fn main() {
-    println!("🚀 RUST: Application starting...");
-    
     tauri::Builder::default()
+        .plugin(log_plugin())
+        .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .plugin(tauri_plugin_autostart::init(
             tauri_plugin_autostart::MacosLauncher::LaunchAgent,
             Some(vec![])
         ))
         .plugin(tauri_plugin_shell::init())
-        .manage(TimerState(Mutex::new(None)))
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
+        .manage(TimerState(Mutex::new(Timer::default())))
+        .manage(hotkeys::HotkeyState::default())
+        .manage(tray_icon::ShownTrayIcon::default())
+        .manage(events::EventBus::default())
+        .manage(stats::StatsState::default())
+        .manage(one_off::OneOffState::default())
         .invoke_handler(tauri::generate_handler![
             trigger_overlay,
+            preview_overlay,
+            replay_last_break,
+            is_overlay_visible,
+            is_tray_available,
+            diagnostics::run_diagnostics,
+            timers::get_timers_status,
+            one_off::schedule_one_off_break,
+            one_off::list_one_off_breaks,
+            one_off::cancel_one_off_break,
+            schedule::upcoming_breaks,
             get_overlay_duration,
+            get_break_config,
             open_settings,
             exit_app,
-            restart_timer
+            confirm_exit,
+            set_autostart,
+            get_autostart_status,
+            hooks::set_shell_hook,
+            open_log_folder,
+            restart_timer,
+            pause_timer,
+            resume_timer,
+            snooze_break,
+            start_focus_mode,
+            snooze_until_tomorrow,
+            cancel_snooze,
+            cancel_focus_mode,
+            postpone_break,
+            update_hotkey,
+            update_dismiss_hotkey,
+            dismiss_overlay,
+            force_close_overlay,
+            skip_break,
+            extend_break,
+            acknowledge_break,
+            stats::get_stats,
+            stats::get_streak,
+            history::export_history_csv,
+            history::clear_history,
+            messages::get_break_message,
+            locale::get_localized_strings,
+            get_confirmation_prompts,
+            monitors::list_monitors,
+            monitors::set_overlay_monitor,
+            confirm_break_prompt,
+            exercises::get_random_exercise,
+            is_in_dnd,
+            next_work_window_start,
+            breaks_remaining_today,
+            get_timer_status,
+            get_session_info,
+            sound::test_sound,
+            settings::export_settings,
+            settings::import_settings,
+            settings::reset_settings,
+            settings::set_interval,
+            settings::set_duration,
+            settings::set_current_and_default_duration,
+            extend_interval_once,
+            schedule::breaks_per_day_estimate,
+            remote_overlay::get_overlay_url,
+            remote_overlay::set_overlay_url,
+            webhook::test_webhook,
+            profiles::list_profiles,
+            profiles::switch_profile,
+            profiles::save_profile,
+            profiles::delete_profile
         ])
         .on_window_event(|window, event| {
             match event {
                 tauri::WindowEvent::CloseRequested { api, .. } => {
                     let label = window.label();
-                    println!("🚪 RUST: Window '{}' close requested", label);
+                    log::info!("🚪 Window '{}' close requested", label);
                     
                     if label == "main" {
-                        println!("🛡️ RUST: Preventing main window from closing");
+                        log::info!("🛡️ Preventing main window from closing");
                         api.prevent_close();
                         let _ = window.hide();
+                        show_tray_hint_once(window.app_handle());
                     }
                 }
                 tauri::WindowEvent::Destroyed => {
-                    println!("💥 RUST: Window '{}' destroyed", window.label());
+                    log::info!("💥 Window '{}' destroyed", window.label());
                 }
                 _ => {}
             }
         })
         .setup(|app| {
-            println!("⚙️ RUST: Running setup...");
+            log::info!("🚀 Application starting...");
+            log::info!("⚙️ Running setup...");
+            let started_at = Instant::now();
+            // The first stretch of work runs from launch.
+            app.state::<TimerState>().0.lock().unwrap().working_since = Some(started_at);
+            app.manage(SessionState {
+                started_at,
+                started_at_local: chrono::Local::now(),
+                breaks_completed: AtomicU64::new(0),
+                breaks_skipped: AtomicU64::new(0),
+            });
             
             if let Some(main_window) = app.get_webview_window("main") {
-                println!("👁️ RUST: Hiding main window");
+                log::info!("👁️ Hiding main window");
                 let _ = main_window.hide();
             }
 
-            let store = app.store("settings.json").expect("Failed to get store");
+            let store = settings::open_settings_store(app.handle())?;
             
-            if store.get("intervalMinutes").is_none() {
-                println!("📝 RUST: Setting default intervalMinutes: 20");
-                let _ = store.set("intervalMinutes", serde_json::json!(20));
-            }
-            if store.get("overlayDurationSeconds").is_none() {
-                println!("📝 RUST: Setting default overlayDurationSeconds: 30");
-                let _ = store.set("overlayDurationSeconds", serde_json::json!(30));
+            settings::apply_default_settings(&store);
+            let _ = store.save();
+
+            if let Err(e) = apply_autostart(app.handle(), get_bool_setting(app.handle(), "autoStart", false)) {
+                log::error!("❌ {}", e);
             }
-            if store.get("autoStart").is_none() {
-                println!("📝 RUST: Setting default autoStart: false");
-                let _ = store.set("autoStart", serde_json::json!(false));
+
+            if store.get("paused").and_then(|v| v.as_bool()).unwrap_or(false) {
+                let remaining = store
+                    .get("pausedRemainingSeconds")
+                    .and_then(|v| v.as_u64())
+                    .map(Duration::from_secs);
+                log::info!("⏸️ Restoring paused state ({:?} remaining)", remaining);
+                {
+                    let timer_state = app.state::<TimerState>();
+                    let mut timer = timer_state.0.lock().unwrap();
+                    timer.paused = true;
+                    timer.paused_remaining = remaining;
+                    timer.suspend_work_clock();
+                }
+                if store.get("pausedUntil").is_some() {
+                    // Already over while the app was closed: resume straight away.
+                    let left = stored_deadline_remaining(app.handle(), "pausedUntil").unwrap_or(Duration::ZERO);
+                    schedule_auto_resume(app.handle(), Some(left));
+                }
             }
-            let _ = store.save();
 
-            println!("🔧 RUST: Building tray menu");
+            register_hotkeys_from_store(app.handle());
+
+            log::info!("🔧 Building tray menu");
             let trigger_item = MenuItem::with_id(app, "trigger", "Start Break Now", true, None::<&str>)?;
+            let trigger_reset_item =
+                MenuItem::with_id(app, "trigger-reset", "Start Break Now (Reset Schedule)", true, None::<&str>)?;
+            let pause_item = MenuItem::with_id(app, "pause", "Pause Nudges", true, None::<&str>)?;
+            let focus_menu = Submenu::with_id(app, "focus", "Focus Mode", true)?;
+            for minutes in FOCUS_PRESETS_MINUTES {
+                let id = format!("{}{}", FOCUS_MENU_ID_PREFIX, minutes);
+                let label = format!("Focus for {} minutes", minutes);
+                focus_menu.append(&MenuItem::with_id(app, id, label, true, None::<&str>)?)?;
+            }
+            let focus_cancel_item =
+                MenuItem::with_id(app, FOCUS_CANCEL_MENU_ID, "Cancel Focus Mode", true, None::<&str>)?;
+            let snooze_day_item =
+                MenuItem::with_id(app, SNOOZE_DAY_MENU_ID, "Snooze Until Tomorrow", true, None::<&str>)?;
+            let profiles_menu = Submenu::with_id(app, "profiles", "Profiles", true)?;
+            profiles::rebuild_profiles_menu(app.handle(), &profiles_menu)?;
             let settings_item = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
             let exit_item = MenuItem::with_id(app, "exit", "Exit", true, None::<&str>)?;
             
             let menu = Menu::with_items(app, &[
                 &trigger_item,
+                &trigger_reset_item,
+                &pause_item,
+                &focus_menu,
+                &snooze_day_item,
+                &profiles_menu,
                 &settings_item,
                 &exit_item,
             ])?;
 
-            println!("🔧 RUST: Building tray icon");
-            let _tray = TrayIconBuilder::with_id("main-tray")
+            log::info!("🔧 Building tray icon");
+            let tray = TrayIconBuilder::with_id("main-tray")
                 .icon(app.default_window_icon().unwrap().clone())
                 .menu(&menu)
-                .menu_on_left_click(false)
+                .show_menu_on_left_click(false)
+                .on_tray_icon_event(|tray, event| {
+                    if let TrayIconEvent::Click {
+                        button: MouseButton::Left,
+                        button_state: MouseButtonState::Up,
+                        ..
+                    } = event
+                    {
+                        on_tray_left_click(tray.app_handle());
+                    }
+                })
                 .on_menu_event(move |app, event| {
-                    println!("📋 RUST: Tray menu event: {}", event.id.as_ref());
+                    log::info!("📋 Tray menu event: {}", event.id.as_ref());
                     match event.id.as_ref() {
                         "trigger" => {
-                            println!("▶️ RUST: Trigger menu item clicked");
+                            log::info!("▶️ Trigger menu item clicked");
+                            let app_clone = app.clone();
+                            tauri::async_runtime::spawn(async move {
+                                let _ = trigger_overlay(app_clone, None).await;
+                            });
+                        }
+                        "trigger-reset" => {
+                            log::info!("▶️ Trigger (reset schedule) menu item clicked");
                             let app_clone = app.clone();
                             tauri::async_runtime::spawn(async move {
-                                let _ = trigger_overlay(app_clone).await;
+                                let _ = trigger_overlay(app_clone, Some(true)).await;
                             });
                         }
+                        "pause" => {
+                            log::info!("⏯️ Pause menu item clicked");
+                            if let Err(e) = toggle_pause(app) {
+                                log::error!("❌ Failed to toggle pause: {}", e);
+                            }
+                        }
                         "settings" => {
-                            println!("⚙️ RUST: Settings menu item clicked");
+                            log::info!("⚙️ Settings menu item clicked");
                             let app_clone = app.clone();
                             tauri::async_runtime::spawn(async move {
                                 let _ = open_settings(app_clone).await;
                             });
                         }
                         "exit" => {
-                            println!("🚪 RUST: Exit menu item clicked - shutting down");
-                            app.exit(0);
+                            log::info!("🚪 Exit menu item clicked");
+                            request_exit(app);
+                        }
+                        FOCUS_CANCEL_MENU_ID => {
+                            log::info!("🎧 Cancel focus mode menu item clicked");
+                            if let Err(e) = cancel_focus_mode(app.clone()) {
+                                log::error!("❌ Failed to cancel focus mode: {}", e);
+                            }
+                        }
+                        SNOOZE_DAY_MENU_ID => {
+                            log::info!("🌙 Snooze until tomorrow menu item clicked");
+                            let snoozed = app.state::<TimerState>().0.lock().unwrap().snoozed_until_tomorrow;
+                            let result = if snoozed {
+                                cancel_snooze(app.clone())
+                            } else {
+                                snooze_until_tomorrow(app.clone())
+                            };
+                            if let Err(e) = result {
+                                log::error!("❌ Failed to change the snooze until tomorrow: {}", e);
+                            }
+                        }
+                        id if id.starts_with(FOCUS_MENU_ID_PREFIX) => {
+                            log::info!("🎧 Focus mode menu item clicked: {}", id);
+                            let minutes = id[FOCUS_MENU_ID_PREFIX.len()..].parse().unwrap_or(0);
+                            if let Err(e) = start_focus_mode(app.clone(), minutes) {
+                                log::error!("❌ Failed to start focus mode: {}", e);
+                            }
+                        }
+                        id if id.starts_with(profiles::PROFILE_MENU_ID_PREFIX) => {
+                            let name = id[profiles::PROFILE_MENU_ID_PREFIX.len()..].to_string();
+                            log::info!("🗂️ Profile menu item clicked: {}", name);
+                            if let Err(e) = profiles::switch_profile(app.clone(), name) {
+                                log::error!("❌ Failed to switch profile: {}", e);
+                                profiles::refresh_profiles_menu(app);
+                            }
                         }
                         _ => {}
                     }
                 })
-                .build(app)?;
+                .build(app);
 
-            println!("⏰ RUST: Starting background timer");
-            start_break_timer(app.handle().clone());
+            match tray {
+                Ok(tray) => {
+                    app.manage(TrayState {
+                        icon: tray,
+                        pause_item,
+                        profiles_menu,
+                        focus_menu,
+                        focus_cancel_item,
+                        snooze_day_item,
+                    });
+                }
+                Err(e) => {
+                    log::warn!("⚠️ No system tray available, running without a tray icon: {}", e);
+                    run_without_tray(app.handle());
+                }
+            }
+
+            tauri::async_runtime::spawn({
+                let app = app.handle().clone();
+                async move {
+                    loop {
+                        tokio::time::sleep(TRAY_REFRESH_INTERVAL).await;
+                        refresh_tray(&app);
+                    }
+                }
+            });
+
+            if let Some(remaining) = stored_deadline_remaining(app.handle(), "focusUntil") {
+                log::info!("🎧 Carrying on focus mode for {:?}", remaining);
+                begin_focus_mode(app.handle(), remaining, false);
+            }
+            if let Some(remaining) = stored_deadline_remaining(app.handle(), "snoozedUntilTomorrow") {
+                log::info!("🌙 Still snoozed until tomorrow, {:?} to go", remaining);
+                begin_focus_mode(app.handle(), remaining, true);
+            }
+
+            control::start(app.handle());
+            resume::start_watchdog(app.handle());
+            profiles::start_schedule_watcher(app.handle());
+            settings_watch::start(app.handle());
+            stats::start_streak_tracking(app.handle());
+            if let Err(e) = history::prune(app.handle()) {
+                log::error!("❌ Failed to prune the break history: {}", e);
+            }
+
+            log::info!("⏰ Starting background timers");
+            start_all_timers(app.handle());
+            stats::start_recording(app.handle());
+            webhook::start(app.handle());
+            events::subscribe(app.handle(), "tray", |app, _| async move { refresh_tray(&app) });
+            one_off::restore(app.handle());
+            end_of_day::start(app.handle());
+            weekday::start_rollover_watch(app.handle());
             
-            println!("✅ RUST: Setup complete!");
+            log::info!("✅ Setup complete!");
             Ok(())
         })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
     
-    println!("🛑 RUST: Application shut down");
-}
\ No newline at end of file
+    log::info!("🛑 Application shut down");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Barrier};
+
+    #[test]
+    fn only_one_of_two_concurrent_breaks_gets_the_screen() {
+        for _ in 0..200 {
+            let state = Arc::new(TimerState(Mutex::new(Timer::default())));
+            let start = Arc::new(Barrier::new(2));
+            let callers: Vec<_> = (0..2)
+                .map(|_| {
+                    let state = state.clone();
+                    let start = start.clone();
+                    std::thread::spawn(move || {
+                        start.wait();
+                        state.0.lock().unwrap().claim_screen()
+                    })
+                })
+                .collect();
+            let winners = callers.into_iter().map(|caller| caller.join().unwrap()).filter(|won| *won).count();
+            assert_eq!(winners, 1);
+            assert!(state.0.lock().unwrap().break_active);
+        }
+    }
+
+    /// Counts a loop as live from when it starts until it is dropped.
+    struct Live(Arc<AtomicU64>);
+
+    impl Drop for Live {
+        fn drop(&mut self) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn restarting_rapidly_leaves_one_live_loop() {
+        let live = Arc::new(AtomicU64::new(0));
+        let most_live = Arc::new(AtomicU64::new(0));
+        let fired = Arc::new(AtomicU64::new(0));
+        let mut current: Option<TimerLoop> = None;
+        for _ in 0..50 {
+            let (live, most_live, fired) = (live.clone(), most_live.clone(), fired.clone());
+            let run = async move {
+                let now_live = live.fetch_add(1, Ordering::SeqCst) + 1;
+                most_live.fetch_max(now_live, Ordering::SeqCst);
+                let _live = Live(live);
+                // Each loop's first break is due shortly after it starts.
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                fired.fetch_add(1, Ordering::SeqCst);
+                std::future::pending::<()>().await;
+            };
+            current = Some(TimerLoop::replace(current.take(), run));
+        }
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(live.load(Ordering::SeqCst), 1);
+        assert_eq!(most_live.load(Ordering::SeqCst), 1);
+        assert_eq!(fired.load(Ordering::SeqCst), 1);
+
+        let last = current.unwrap();
+        last.cancel.cancel();
+        last.handle.await.unwrap();
+        assert_eq!(live.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn the_screen_is_free_again_once_the_break_closes() {
+        let mut timer = Timer::default();
+        assert!(timer.claim_screen());
+        assert!(!timer.claim_screen());
+        timer.break_active = false;
+        assert!(timer.claim_screen());
+    }
+}
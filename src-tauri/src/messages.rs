@@ -0,0 +1,132 @@
+//! User-defined break messages from `breakMessages`, drawn at random across breaks.
+//!
+//! Each entry is `{text, weight, categories}`; a plain string still works and counts as
+//! weight 1 with no categories. An entry with categories is only shown on breaks whose kind
+//! ("micro", "long", "end-of-day") is among them; one without fits every break. Among the
+//! entries that fit, one is drawn in proportion to its `weight`, skipping the one shown
+//! last time when there is a choice.
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::{BreakKind, TimerState, SETTINGS_STORE};
+
+fn default_weight() -> u64 {
+    1
+}
+
+/// One entry of `breakMessages`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(from = "MessageEntry")]
+pub struct BreakMessage {
+    pub text: String,
+    /// Relative chance of being drawn among the entries that fit the break.
+    pub weight: u64,
+    /// Break kinds the message is for; empty for all of them.
+    pub categories: Vec<String>,
+}
+
+/// How an entry may be written: the original plain string, or the full form.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MessageEntry {
+    Plain(String),
+    Full {
+        text: String,
+        #[serde(default = "default_weight")]
+        weight: u64,
+        #[serde(default)]
+        categories: Vec<String>,
+    },
+}
+
+impl From<MessageEntry> for BreakMessage {
+    fn from(entry: MessageEntry) -> Self {
+        match entry {
+            MessageEntry::Plain(text) => BreakMessage {
+                text,
+                weight: 1,
+                categories: Vec::new(),
+            },
+            MessageEntry::Full { text, weight, categories } => BreakMessage { text, weight, categories },
+        }
+    }
+}
+
+impl BreakMessage {
+    fn fits(&self, kind: &str) -> bool {
+        self.categories.is_empty() || self.categories.iter().any(|category| category == kind)
+    }
+}
+
+/// Checks the entries of `breakMessages`.
+pub fn validate_break_messages(messages: &[BreakMessage]) -> Result<(), String> {
+    messages.iter().try_for_each(|message| {
+        if message.weight == 0 || message.weight > 100 {
+            Err(format!("message '{}' needs a weight between 1 and 100", message.text))
+        } else {
+            Ok(())
+        }
+    })
+}
+
+/// Draws the message for a `kind` break from `messages`, avoiding `last_index` when another
+/// entry fits. Returns the chosen index along with the message, or `None` if none fits.
+fn pick_message<'a>(messages: &'a [BreakMessage], kind: &str, last_index: Option<u64>) -> Option<(usize, &'a str)> {
+    let fitting: Vec<usize> = (0..messages.len())
+        .filter(|&index| messages[index].fits(kind) && !messages[index].text.trim().is_empty())
+        .collect();
+    let candidates: Vec<usize> = match fitting.as_slice() {
+        [_, _, ..] => fitting.iter().copied().filter(|&index| Some(index as u64) != last_index).collect(),
+        _ => fitting,
+    };
+    let weights: Vec<u64> = candidates.iter().map(|&index| messages[index].weight).collect();
+    let index = *candidates.get(crate::reminders::weighted_index(&weights))?;
+    Some((index, messages[index].text.as_str()))
+}
+
+/// Returns the message for the current break. The first call for a break draws one from
+/// `breakMessages` for `break_type` (the break on screen if omitted), persisting
+/// `lastMessageIndex` so consecutive breaks don't repeat; later calls (e.g. from the
+/// overlays on other monitors) get the same message.
+#[tauri::command]
+pub fn get_break_message(app: AppHandle, break_type: Option<String>) -> Result<String, String> {
+    log::debug!("💬 get_break_message command received! ({:?})", break_type);
+    let timer_state = app.state::<TimerState>();
+    let mut timer = timer_state.0.lock().unwrap();
+    if let Some(message) = &timer.break_message {
+        return Ok(message.clone());
+    }
+
+    let kind = break_type.unwrap_or_else(|| timer.current_break.as_str().to_string());
+    let message = pick_next_message(&app, &kind)?;
+    timer.break_message = Some(message.clone());
+    Ok(message)
+}
+
+/// Falls back to the built-in message for the break, in the configured language, when no
+/// entry fits.
+fn pick_next_message(app: &AppHandle, kind: &str) -> Result<String, String> {
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+
+    let messages: Vec<BreakMessage> = store
+        .get("breakMessages")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    let last_index = store.get("lastMessageIndex").and_then(|v| v.as_u64());
+
+    let Some((index, message)) = pick_message(&messages, kind, last_index) else {
+        let key = match kind {
+            k if k == BreakKind::Long.as_str() => "break.long.message",
+            k if k == BreakKind::EndOfDay.as_str() => "break.endOfDay.message",
+            _ => "break.micro.message",
+        };
+        return Ok(crate::locale::text(app, key));
+    };
+
+    store.set("lastMessageIndex", json!(index));
+    store.save().map_err(|e| e.to_string())?;
+    Ok(message.to_string())
+}
@@ -0,0 +1,204 @@
+//! Which displays get a break overlay.
+//!
+//! `overlayMonitors` is either `"all"` or a list of monitor names as reported by
+//! `list_monitors`. Selected monitors that aren't connected are ignored; if none of them
+//! are, the primary monitor gets the overlay so a break is never shown nowhere.
+//! `overlayMonitorPreference`, left by `set_overlay_monitor`, puts the next overlay on that
+//! one monitor instead, once.
+//!
+//! With `primaryContentOnly` only the active monitor, the one under the mouse cursor, gets
+//! the full break screen and the others are just dimmed. When the cursor can't be read or
+//! sits between monitors, the primary monitor counts as active.
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Monitor, WebviewWindow};
+use tauri_plugin_store::StoreExt;
+
+use crate::{appearance, TimerState, SETTINGS_STORE};
+
+/// Where `set_overlay_monitor` leaves a monitor for the next overlay.
+const PREFERENCE_KEY: &str = "overlayMonitorPreference";
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorInfo {
+    name: String,
+    width: u32,
+    height: u32,
+    is_primary: bool,
+    scale_factor: f64,
+}
+
+/// What an overlay window shows, passed to its page in `get_break_config`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OverlayRole {
+    /// The full break screen.
+    Content,
+    /// A plain dimmed screen (`primaryContentOnly`).
+    Dim,
+}
+
+/// Identifies a monitor in `overlayMonitors`. Some platforms report no name, so those fall
+/// back to their position in the list.
+fn monitor_name(monitor: &Monitor, index: usize) -> String {
+    monitor
+        .name()
+        .cloned()
+        .unwrap_or_else(|| format!("Monitor {}", index + 1))
+}
+
+fn is_same_monitor(a: &Monitor, b: &Monitor) -> bool {
+    a.name() == b.name() && a.position() == b.position()
+}
+
+#[tauri::command]
+pub fn list_monitors(app: AppHandle) -> Result<Vec<MonitorInfo>, String> {
+    log::debug!("🖥️ list_monitors command received!");
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+    let primary = app.primary_monitor().ok().flatten();
+    Ok(monitors
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| MonitorInfo {
+            name: monitor_name(monitor, index),
+            width: monitor.size().width,
+            height: monitor.size().height,
+            is_primary: primary.as_ref().is_some_and(|primary| is_same_monitor(primary, monitor)),
+            scale_factor: monitor.scale_factor(),
+        })
+        .collect())
+}
+
+/// Shows the overlay on monitor `monitor_index` of `list_monitors` alone. A break on screen
+/// moves there at once; otherwise the choice is kept in `overlayMonitorPreference` for the
+/// next overlay to open. `overlayMonitors` is left as it is either way.
+#[tauri::command]
+pub fn set_overlay_monitor(app: AppHandle, monitor_index: usize) -> Result<(), String> {
+    log::debug!("🖥️ set_overlay_monitor command received ({})", monitor_index);
+    let monitors = app.available_monitors().map_err(|e| e.to_string())?;
+    let Some(monitor) = monitors.get(monitor_index) else {
+        return Err(format!(
+            "No monitor {}, there are {} connected",
+            monitor_index,
+            monitors.len()
+        ));
+    };
+    let name = monitor_name(monitor, monitor_index);
+
+    let overlays = crate::overlay_windows(&app);
+    if overlays.is_empty() {
+        let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+        store.set(PREFERENCE_KEY, json!(name));
+        store.save().map_err(|e| e.to_string())?;
+        log::info!("✅ The next overlay will open on '{}'", name);
+        return Ok(());
+    }
+
+    // The window with the break content moves, not one of the dimmed ones. Closing an
+    // overlay ends the break, so the others are only hidden.
+    let content = app.state::<TimerState>().0.lock().unwrap().content_overlay.clone();
+    let moved = content
+        .and_then(|label| overlays.iter().find(|overlay| overlay.label() == label))
+        .unwrap_or(&overlays[0]);
+    move_to_monitor(&app, moved, monitor).map_err(|e| e.to_string())?;
+    log::info!("🖥️ Moved the overlay to '{}'", name);
+    for overlay in overlays.iter().filter(|overlay| overlay.label() != moved.label()) {
+        let _ = overlay.hide();
+    }
+    Ok(())
+}
+
+fn move_to_monitor(app: &AppHandle, window: &WebviewWindow, monitor: &Monitor) -> tauri::Result<()> {
+    let mode = appearance::overlay_mode(app);
+    let size = appearance::overlay_size(app, mode, Some(monitor));
+    window.set_fullscreen(false)?;
+    window.set_position(appearance::overlay_position(mode, monitor, size))?;
+    window.set_size(size)?;
+    window.set_fullscreen(mode == appearance::OverlayMode::Fullscreen)
+}
+
+/// The connected monitors that should show the overlay. Empty only if no monitors could be
+/// listed at all.
+pub fn overlay_monitors(app: &AppHandle) -> Vec<Monitor> {
+    // Monitors can come and go between breaks, so the list is rebuilt every time.
+    let monitors = app.available_monitors().unwrap_or_else(|e| {
+        log::warn!("⚠️ Could not list monitors: {}", e);
+        Vec::new()
+    });
+    if let Some(monitor) = take_preference(app, &monitors) {
+        return vec![monitor];
+    }
+    let selected: Vec<String> = match crate::get_setting(app, "overlayMonitors") {
+        Some(Value::Array(names)) => names.iter().filter_map(|v| v.as_str().map(str::to_string)).collect(),
+        _ => return monitors,
+    };
+
+    let chosen: Vec<Monitor> = monitors
+        .iter()
+        .enumerate()
+        .filter(|(index, monitor)| selected.contains(&monitor_name(monitor, *index)))
+        .map(|(_, monitor)| monitor.clone())
+        .collect();
+    if !chosen.is_empty() {
+        return chosen;
+    }
+
+    log::warn!("⚠️ None of the selected monitors are connected, using the primary one");
+    let primary = app.primary_monitor().ok().flatten();
+    match primary.or_else(|| monitors.into_iter().next()) {
+        Some(monitor) => vec![monitor],
+        None => Vec::new(),
+    }
+}
+
+/// The monitor `set_overlay_monitor` picked for this overlay, if it is still connected. The
+/// preference is used up either way.
+fn take_preference(app: &AppHandle, monitors: &[Monitor]) -> Option<Monitor> {
+    let name = crate::get_string_setting(app, PREFERENCE_KEY, "");
+    if name.is_empty() {
+        return None;
+    }
+    if let Ok(store) = app.store(SETTINGS_STORE) {
+        store.delete(PREFERENCE_KEY);
+        if let Err(e) = store.save() {
+            log::error!("❌ Failed to clear {}: {}", PREFERENCE_KEY, e);
+        }
+    }
+    let monitor = monitors
+        .iter()
+        .enumerate()
+        .find(|(index, monitor)| monitor_name(monitor, *index) == name)
+        .map(|(_, monitor)| monitor.clone());
+    if monitor.is_none() {
+        log::warn!("⚠️ Preferred overlay monitor '{}' is not connected, ignoring it", name);
+    }
+    monitor
+}
+
+fn contains(monitor: &Monitor, x: f64, y: f64) -> bool {
+    let position = monitor.position();
+    let size = monitor.size();
+    x >= f64::from(position.x)
+        && y >= f64::from(position.y)
+        && x < f64::from(position.x) + f64::from(size.width)
+        && y < f64::from(position.y) + f64::from(size.height)
+}
+
+/// Index into `monitors` of the one the user is working on: under the cursor, else the
+/// primary monitor, else the first.
+pub fn active_monitor_index(app: &AppHandle, monitors: &[Monitor]) -> usize {
+    match app.cursor_position() {
+        Ok(cursor) => {
+            if let Some(index) = monitors.iter().position(|monitor| contains(monitor, cursor.x, cursor.y)) {
+                return index;
+            }
+            log::debug!("🖱️ Cursor at ({}, {}) is on none of the overlay monitors", cursor.x, cursor.y);
+        }
+        Err(e) => log::debug!("🖱️ Could not read the cursor position: {}", e),
+    }
+    let primary = app.primary_monitor().ok().flatten();
+    primary
+        .and_then(|primary| monitors.iter().position(|monitor| monitor.position() == primary.position()))
+        .unwrap_or(0)
+}
@@ -0,0 +1,204 @@
+//! One-off breaks at a set wall-clock time ("remind me at 15:00"), apart from the recurring
+//! schedule.
+//!
+//! Each gets a task of its own that shows the break at its time, waiting its turn if another
+//! break is on screen, the same way the extra `timers` do; its break is counted under
+//! `ONE_OFF_NAME`. Pending ones are kept in the store under `oneOffBreaks`, so they survive
+//! a restart; any whose time passed while the app was closed are dropped.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
+use tokio_util::sync::CancellationToken;
+
+use crate::schedule::Clock;
+use crate::timers::TimerConfig;
+use crate::{time_window, BreakKind, SystemClock, SETTINGS_STORE};
+
+/// Internal key holding the pending one-off breaks.
+const STORE_KEY: &str = "oneOffBreaks";
+/// Name the overlay and the stats give one-off breaks.
+const ONE_OFF_NAME: &str = "One-off break";
+/// How often a pending break checks the clock. Polling, rather than one long sleep, keeps
+/// it on time across suspends.
+const CHECK_PERIOD: Duration = Duration::from_secs(15);
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OneOffBreak {
+    pub id: u64,
+    /// Local time it fires at, as `YYYY-MM-DDTHH:MM:SS`.
+    pub at: String,
+    /// Text for the overlay; the usual micro-break message if `None`.
+    pub message: Option<String>,
+}
+
+impl OneOffBreak {
+    fn due_at(&self) -> Option<NaiveDateTime> {
+        NaiveDateTime::parse_from_str(&self.at, TIMESTAMP_FORMAT).ok()
+    }
+}
+
+/// The pending one-off breaks by id, each with the token that cancels its task.
+#[derive(Default)]
+pub struct OneOffState(Mutex<HashMap<u64, (OneOffBreak, CancellationToken)>>);
+
+/// Reads `at` as a local `YYYY-MM-DDTHH:MM[:SS]` timestamp, or as `HH:MM` for the next time
+/// the clock shows it. It has to be in the future.
+fn parse_at(at: &str, now: NaiveDateTime) -> Result<NaiveDateTime, String> {
+    let at = at.trim();
+    let due = ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(at, format).ok());
+    let due = match due {
+        Some(due) => due,
+        None => {
+            let time: NaiveTime = time_window::parse_hhmm(at)
+                .map_err(|_| format!("'{}' is not a time (HH:MM) or local date and time", at))?;
+            let today = now.date().and_time(time);
+            if today > now { today } else { today + chrono::Days::new(1) }
+        }
+    };
+    if due <= now {
+        return Err(format!("{} has already passed", due.format(TIMESTAMP_FORMAT)));
+    }
+    Ok(due)
+}
+
+fn save(app: &AppHandle) {
+    let pending = list(app);
+    let store = match app.store(SETTINGS_STORE) {
+        Ok(store) => store,
+        Err(e) => {
+            log::error!("❌ Failed to open the settings store: {}", e);
+            return;
+        }
+    };
+    store.set(STORE_KEY, serde_json::json!(pending));
+    if let Err(e) = store.save() {
+        log::error!("❌ Failed to save {}: {}", STORE_KEY, e);
+    }
+}
+
+fn list(app: &AppHandle) -> Vec<OneOffBreak> {
+    let state = app.state::<OneOffState>();
+    let mut pending: Vec<OneOffBreak> = state.0.lock().unwrap().values().map(|(entry, _)| entry.clone()).collect();
+    pending.sort_by(|a, b| a.at.cmp(&b.at).then(a.id.cmp(&b.id)));
+    pending
+}
+
+/// Starts the task for `entry` and tracks it. The caller saves the list.
+fn spawn(app: &AppHandle, entry: OneOffBreak) {
+    let Some(due) = entry.due_at() else {
+        log::error!("❌ Dropping one-off break {} with unreadable time '{}'", entry.id, entry.at);
+        return;
+    };
+    let cancel = CancellationToken::new();
+    log::info!("⏰ One-off break {} set for {}", entry.id, entry.at);
+    // Tracked before the task starts, so a break that is due right away still finds itself.
+    app.state::<OneOffState>().0.lock().unwrap().insert(entry.id, (entry.clone(), cancel.clone()));
+    tauri::async_runtime::spawn({
+        let app = app.clone();
+        let cancel = cancel.clone();
+        let id = entry.id;
+        let message = entry.message.clone();
+        async move {
+            tokio::select! {
+                biased;
+                _ = cancel.cancelled() => log::info!("🛑 One-off break {} canceled", id),
+                _ = fire_at(due) => {
+                    app.state::<OneOffState>().0.lock().unwrap().remove(&id);
+                    save(&app);
+                    show(&app, message).await;
+                }
+            }
+        }
+    });
+}
+
+/// Waits until the local clock reaches `due`.
+async fn fire_at(due: NaiveDateTime) {
+    loop {
+        let left = (due - SystemClock.local()).to_std().unwrap_or(Duration::ZERO);
+        if left.is_zero() {
+            return;
+        }
+        tokio::time::sleep(left.min(CHECK_PERIOD)).await;
+    }
+}
+
+async fn show(app: &AppHandle, message: Option<String>) {
+    let config = TimerConfig {
+        name: ONE_OFF_NAME.to_string(),
+        interval_minutes: 0,
+        duration_seconds: crate::break_duration_seconds(app, BreakKind::Micro),
+        icon: "⏰".to_string(),
+        message: message.unwrap_or_else(|| crate::locale::text(app, "break.micro.message")),
+    };
+    crate::timers::wait_for_screen(app).await;
+    crate::close_preview(app);
+    log::info!("⏰ One-off break due! Showing overlay window");
+    crate::open_overlays(app, BreakKind::Micro, false, Some(&config)).await;
+}
+
+/// Picks the pending one-off breaks back up after a restart, dropping any whose time passed.
+pub fn restore(app: &AppHandle) {
+    let saved: Vec<OneOffBreak> = crate::get_setting(app, STORE_KEY)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    let now = SystemClock.local();
+    for entry in saved {
+        if entry.due_at().is_some_and(|due| due > now) {
+            spawn(app, entry);
+        } else {
+            log::info!("⏭️ One-off break {} at {} passed while the app was closed", entry.id, entry.at);
+        }
+    }
+    save(app);
+}
+
+/// Schedules a single break at `at`, a local `YYYY-MM-DDTHH:MM` time or the next `HH:MM`,
+/// leaving the recurring schedule alone. `message` replaces the usual text.
+#[tauri::command]
+pub fn schedule_one_off_break(app: AppHandle, at: String, message: Option<String>) -> Result<OneOffBreak, String> {
+    log::debug!("⏰ schedule_one_off_break command received ({})", at);
+    let due = parse_at(&at, SystemClock.local())?;
+    let id = {
+        let state = app.state::<OneOffState>();
+        let pending = state.0.lock().unwrap();
+        pending.keys().max().map_or(1, |id| id + 1)
+    };
+    let entry = OneOffBreak {
+        id,
+        at: due.format(TIMESTAMP_FORMAT).to_string(),
+        message: message.filter(|message| !message.trim().is_empty()),
+    };
+    spawn(&app, entry.clone());
+    save(&app);
+    Ok(entry)
+}
+
+/// The pending one-off breaks, soonest first.
+#[tauri::command]
+pub fn list_one_off_breaks(app: AppHandle) -> Vec<OneOffBreak> {
+    log::debug!("⏰ list_one_off_breaks command received!");
+    list(&app)
+}
+
+#[tauri::command]
+pub fn cancel_one_off_break(app: AppHandle, id: u64) -> Result<(), String> {
+    log::debug!("⏰ cancel_one_off_break command received ({})", id);
+    let removed = app.state::<OneOffState>().0.lock().unwrap().remove(&id);
+    let Some((_, cancel)) = removed else {
+        return Err(format!("There is no pending one-off break {}", id));
+    };
+    cancel.cancel();
+    save(&app);
+    Ok(())
+}
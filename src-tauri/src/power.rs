@@ -0,0 +1,173 @@
+//! Power source detection, used to space breaks out further while running on battery, and
+//! the OS's own suspend/resume notifications.
+
+/// True when the machine is running on battery rather than mains power.
+///
+/// Returns false on platforms where the power source can't be determined, so the regular
+/// interval applies.
+#[cfg(target_os = "windows")]
+pub fn on_battery() -> bool {
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+    const AC_OFFLINE: u8 = 0;
+
+    let mut status: SYSTEM_POWER_STATUS = unsafe { std::mem::zeroed() };
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+        return false;
+    }
+    status.ACLineStatus == AC_OFFLINE
+}
+
+#[cfg(target_os = "linux")]
+pub fn on_battery() -> bool {
+    let Ok(supplies) = std::fs::read_dir("/sys/class/power_supply") else {
+        return false;
+    };
+    let read = |path: &std::path::Path, name: &str| {
+        std::fs::read_to_string(path.join(name))
+            .map(|value| value.trim().to_string())
+            .unwrap_or_default()
+    };
+
+    let mut discharging = false;
+    for supply in supplies.flatten() {
+        let path = supply.path();
+        match read(&path, "type").as_str() {
+            "Mains" if read(&path, "online") == "1" => return false,
+            "Battery" if read(&path, "status") == "Discharging" => discharging = true,
+            _ => {}
+        }
+    }
+    discharging
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn on_battery() -> bool {
+    false
+}
+
+/// A system sleep transition, as reported by the OS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SleepEvent {
+    Sleeping,
+    Woke,
+}
+
+/// Calls `on_event` as the system goes to sleep and wakes up again, for as long as the app runs.
+///
+/// Uses `PBT_APMSUSPEND`/`PBT_APMRESUMEAUTOMATIC` (the `WM_POWERBROADCAST` events) on Windows,
+/// `NSWorkspace` sleep notifications on macOS and logind's `PrepareForSleep` on Linux. Where
+/// none are available, or subscribing fails, nothing is ever reported.
+#[cfg(target_os = "windows")]
+pub fn watch_sleep(on_event: impl Fn(SleepEvent) + Send + Sync + 'static) {
+    use std::ffi::c_void;
+    use windows_sys::Win32::System::Power::{
+        PowerRegisterSuspendResumeNotification, DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        DEVICE_NOTIFY_CALLBACK, PBT_APMRESUMEAUTOMATIC, PBT_APMSUSPEND,
+    };
+
+    type Callback = Box<dyn Fn(SleepEvent) + Send + Sync>;
+
+    unsafe extern "system" fn on_power_broadcast(context: *const c_void, kind: u32, _setting: *const c_void) -> u32 {
+        let on_event = unsafe { &*context.cast::<Callback>() };
+        match kind {
+            PBT_APMSUSPEND => on_event(SleepEvent::Sleeping),
+            PBT_APMRESUMEAUTOMATIC => on_event(SleepEvent::Woke),
+            _ => {}
+        }
+        0
+    }
+
+    // Never unregistered, so the callback and its parameters live as long as the process.
+    let on_event: &'static mut Callback = Box::leak(Box::new(Box::new(on_event)));
+    let parameters = Box::leak(Box::new(DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS {
+        Callback: Some(on_power_broadcast),
+        Context: (on_event as *mut Callback).cast(),
+    }));
+    let mut registration = std::ptr::null_mut();
+    let status = unsafe {
+        PowerRegisterSuspendResumeNotification(
+            DEVICE_NOTIFY_CALLBACK,
+            (parameters as *mut DEVICE_NOTIFY_SUBSCRIBE_PARAMETERS).cast(),
+            &mut registration,
+        )
+    };
+    if status != 0 {
+        log::warn!("⚠️ Can't subscribe to suspend notifications (error {})", status);
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn watch_sleep(on_event: impl Fn(SleepEvent) + Send + Sync + 'static) {
+    use std::ptr::NonNull;
+    use std::sync::Arc;
+
+    use block2::RcBlock;
+    use objc2_app_kit::{NSWorkspace, NSWorkspaceDidWakeNotification, NSWorkspaceWillSleepNotification};
+    use objc2_foundation::NSNotification;
+
+    let on_event = Arc::new(on_event);
+    let center = NSWorkspace::sharedWorkspace().notificationCenter();
+    let names = unsafe {
+        [
+            (NSWorkspaceWillSleepNotification, SleepEvent::Sleeping),
+            (NSWorkspaceDidWakeNotification, SleepEvent::Woke),
+        ]
+    };
+    for (name, event) in names {
+        let on_event = on_event.clone();
+        let block = RcBlock::new(move |_: NonNull<NSNotification>| on_event(event));
+        let observer = unsafe { center.addObserverForName_object_queue_usingBlock(Some(name), None, None, &block) };
+        // Observing for the app's whole lifetime, so the token is never handed back.
+        std::mem::forget(observer);
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn watch_sleep(on_event: impl Fn(SleepEvent) + Send + Sync + 'static) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = watch_logind(&on_event).await {
+            log::warn!("⚠️ No suspend notifications from logind: {}", e);
+        }
+    });
+}
+
+#[cfg(target_os = "linux")]
+async fn watch_logind(on_event: &(impl Fn(SleepEvent) + Send + Sync)) -> zbus::Result<()> {
+    use futures_util::StreamExt;
+    use zbus::zvariant::OwnedFd;
+
+    let connection = zbus::Connection::system().await?;
+    let manager = zbus::Proxy::new(
+        &connection,
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1",
+        "org.freedesktop.login1.Manager",
+    )
+    .await?;
+    // A delay lock holds the suspend off until `Sleeping` has been handled; without one the
+    // signal may only be read after waking up.
+    let take_lock = || async {
+        manager
+            .call::<_, _, OwnedFd>("Inhibit", &("sleep", "Nudge", "Noting when the next break is due", "delay"))
+            .await
+    };
+    let mut sleep_lock = Some(take_lock().await?);
+    let mut signals = manager.receive_signal("PrepareForSleep").await?;
+    while let Some(signal) = signals.next().await {
+        if signal.body().deserialize::<bool>()? {
+            on_event(SleepEvent::Sleeping);
+            sleep_lock = None;
+        } else {
+            on_event(SleepEvent::Woke);
+            sleep_lock = Some(take_lock().await?);
+        }
+    }
+    drop(sleep_lock);
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+pub fn watch_sleep(_on_event: impl Fn(SleepEvent) + Send + Sync + 'static) {}
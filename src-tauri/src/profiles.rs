@@ -0,0 +1,250 @@
+//! Named sets of settings ("Work", "Gaming", ...) that can be switched between in one go.
+//!
+//! Profiles live in the settings store under `profiles`, a map from profile name to the
+//! settings it overrides. Switching copies those settings over the top-level ones, which
+//! are what the rest of the app reads, and records the name in `activeProfile`.
+//!
+//! `profileSchedules` switches profiles by time of day. It is a list of time windows, each
+//! naming a profile; where windows overlap, the one listed first wins. A background check
+//! switches when the clock crosses into a different scheduled profile, so a manual switch
+//! holds until the next boundary.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+use tauri::menu::{CheckMenuItem, MenuItem};
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::time_window::{TimeWindow, TimeWindowSetting};
+use crate::{settings, TrayState, SETTINGS_STORE};
+
+/// How often the profile schedule is checked for a boundary.
+const SCHEDULE_CHECK_PERIOD: Duration = Duration::from_secs(30);
+
+/// Prefix of the tray menu item ids that switch to a profile, e.g. `profile:Work`.
+pub const PROFILE_MENU_ID_PREFIX: &str = "profile:";
+
+/// One entry of `profileSchedules`: `profile` is active during the window.
+#[derive(Deserialize)]
+pub struct ProfileSchedule {
+    pub profile: String,
+    #[serde(flatten)]
+    pub window: TimeWindowSetting,
+}
+
+/// Payload of the app-wide `profile-switched` event.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileSwitched {
+    name: String,
+    /// Whether `profileSchedules` made the switch rather than the user.
+    automatic: bool,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileList {
+    active: Option<String>,
+    names: Vec<String>,
+}
+
+/// Checks the settings a profile holds. Profiles can't contain other profiles.
+pub fn validate_profile_settings(profile: &Map<String, Value>) -> Result<(), String> {
+    let problems: Vec<String> = profile
+        .iter()
+        .filter_map(|(key, value)| match key.as_str() {
+            "profiles" | "profileSchedules" | "activeProfile" => Some(format!("'{}' can't be part of a profile", key)),
+            _ => settings::validate_value(key, value).err(),
+        })
+        .collect();
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems.join("; "))
+    }
+}
+
+fn load_profiles(app: &AppHandle) -> Result<Map<String, Value>, String> {
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    Ok(store
+        .get("profiles")
+        .and_then(|v| serde_json::from_value::<Map<String, Value>>(v).ok())
+        .unwrap_or_default())
+}
+
+pub fn active_profile(app: &AppHandle) -> Option<String> {
+    let store = app.store(SETTINGS_STORE).ok()?;
+    store
+        .get("activeProfile")
+        .and_then(|v| v.as_str().map(str::to_string))
+        .filter(|name| !name.is_empty())
+}
+
+#[tauri::command]
+pub fn list_profiles(app: AppHandle) -> Result<ProfileList, String> {
+    log::debug!("🗂️ list_profiles command received!");
+    let names = load_profiles(&app)?.keys().cloned().collect();
+    Ok(ProfileList {
+        active: active_profile(&app),
+        names,
+    })
+}
+
+/// Creates or replaces the profile `name`. Saving the active profile applies it straight away.
+#[tauri::command]
+pub fn save_profile(app: AppHandle, name: String, settings: Map<String, Value>) -> Result<(), String> {
+    log::debug!("🗂️ save_profile command received for '{}'", name);
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Profile name can't be empty".to_string());
+    }
+    validate_profile_settings(&settings)?;
+
+    let mut profiles = load_profiles(&app)?;
+    profiles.insert(name.clone(), Value::Object(settings));
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    store.set("profiles", Value::Object(profiles));
+    store.save().map_err(|e| e.to_string())?;
+    log::info!("✅ Profile '{}' saved", name);
+
+    if active_profile(&app).as_deref() == Some(name.as_str()) {
+        return switch_profile(app, name);
+    }
+    refresh_profiles_menu(&app);
+    Ok(())
+}
+
+/// Applies the settings of profile `name` and restarts the timer with them. The switch
+/// holds until the profile schedule next crosses a boundary.
+#[tauri::command]
+pub fn switch_profile(app: AppHandle, name: String) -> Result<(), String> {
+    log::debug!("🗂️ switch_profile command received for '{}'", name);
+    apply_profile(&app, &name, false)
+}
+
+fn apply_profile(app: &AppHandle, name: &str, automatic: bool) -> Result<(), String> {
+    let profiles = load_profiles(app)?;
+    let Some(profile) = profiles.get(name).and_then(Value::as_object) else {
+        return Err(format!("No profile named '{}'", name));
+    };
+    validate_profile_settings(profile)?;
+
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    for (key, value) in profile {
+        store.set(key.clone(), value.clone());
+    }
+    store.set("activeProfile", json!(name));
+    store.save().map_err(|e| e.to_string())?;
+    log::info!("✅ Switched to profile '{}'", name);
+
+    let switched = ProfileSwitched {
+        name: name.to_string(),
+        automatic,
+    };
+    if let Err(e) = app.emit("profile-switched", switched) {
+        log::error!("❌ Failed to emit profile-switched: {}", e);
+    }
+    refresh_profiles_menu(app);
+    crate::register_hotkeys_from_store(app);
+    let restarted = crate::restart_timer(app.clone()).map_err(|problems| problems.join("; "));
+    crate::tray_icon::reset(app);
+    restarted
+}
+
+/// The profile `profileSchedules` calls for right now: the first entry whose window
+/// contains the current time.
+fn scheduled_profile(app: &AppHandle) -> Option<String> {
+    let schedules: Vec<ProfileSchedule> = crate::get_setting(app, "profileSchedules")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    let now = chrono::Local::now().naive_local();
+    schedules.into_iter().find_map(|schedule| {
+        match TimeWindow::from_setting(&schedule.window) {
+            Ok(window) if window.contains(now) => Some(schedule.profile),
+            Ok(_) => None,
+            Err(e) => {
+                log::error!("❌ Ignoring invalid schedule for profile '{}': {}", schedule.profile, e);
+                None
+            }
+        }
+    })
+}
+
+/// Switches profiles whenever the clock crosses into a window of `profileSchedules` that
+/// names a different one. Only boundaries trigger a switch, so the user's own choice
+/// stands in between.
+pub fn start_schedule_watcher(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut last_scheduled: Option<String> = None;
+        loop {
+            let scheduled = scheduled_profile(&app);
+            if scheduled != last_scheduled {
+                if let Some(name) = scheduled.as_deref() {
+                    if active_profile(&app).as_deref() != Some(name) {
+                        log::info!("🗂️ Profile schedule switching to '{}'", name);
+                        if let Err(e) = apply_profile(&app, name, true) {
+                            log::error!("❌ Scheduled switch to profile '{}' failed: {}", name, e);
+                        }
+                    }
+                }
+                last_scheduled = scheduled;
+            }
+            tokio::time::sleep(SCHEDULE_CHECK_PERIOD).await;
+        }
+    });
+}
+
+/// Removes profile `name`. The active profile can't be deleted; switch away from it first.
+#[tauri::command]
+pub fn delete_profile(app: AppHandle, name: String) -> Result<(), String> {
+    log::debug!("🗂️ delete_profile command received for '{}'", name);
+    if active_profile(&app).as_deref() == Some(name.as_str()) {
+        return Err(format!("'{}' is the active profile", name));
+    }
+    let mut profiles = load_profiles(&app)?;
+    if profiles.remove(&name).is_none() {
+        return Err(format!("No profile named '{}'", name));
+    }
+
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    store.set("profiles", Value::Object(profiles));
+    store.save().map_err(|e| e.to_string())?;
+    log::info!("✅ Profile '{}' deleted", name);
+
+    refresh_profiles_menu(&app);
+    Ok(())
+}
+
+/// Rebuilds the tray's "Profiles" submenu, checking the active profile.
+pub fn refresh_profiles_menu(app: &AppHandle) {
+    let Some(tray) = app.try_state::<TrayState>() else {
+        return;
+    };
+    if let Err(e) = rebuild_profiles_menu(app, &tray.profiles_menu) {
+        log::error!("❌ Failed to update the profiles menu: {}", e);
+    }
+}
+
+pub fn rebuild_profiles_menu(app: &AppHandle, menu: &tauri::menu::Submenu<tauri::Wry>) -> tauri::Result<()> {
+    for item in menu.items()? {
+        menu.remove(&item)?;
+    }
+
+    let names: Vec<String> = load_profiles(app).unwrap_or_default().keys().cloned().collect();
+    if names.is_empty() {
+        let placeholder = MenuItem::new(app, "No profiles", false, None::<&str>)?;
+        return menu.append(&placeholder);
+    }
+
+    let active = active_profile(app);
+    for name in names {
+        let checked = active.as_deref() == Some(name.as_str());
+        let id = format!("{}{}", PROFILE_MENU_ID_PREFIX, name);
+        let item = CheckMenuItem::with_id(app, id, &name, true, checked, None::<&str>)?;
+        menu.append(&item)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,95 @@
+//! User-defined reminder types ("Drink water", "Check your posture", ...) taking turns
+//! across micro-breaks.
+//!
+//! `reminderTypes` lists them, each with a name, an icon (usually an emoji) and a message.
+//! With `reminderRotation` set to "round-robin" they come up in order, persisting
+//! `lastReminderIndex`; with "weighted" one is drawn at random in proportion to its
+//! `weight`. An empty list leaves micro-breaks as plain eye-rest breaks.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::SETTINGS_STORE;
+
+fn default_weight() -> u64 {
+    1
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReminderType {
+    pub name: String,
+    #[serde(default)]
+    pub icon: String,
+    pub message: String,
+    /// Relative chance of being picked under "weighted" rotation.
+    #[serde(default = "default_weight")]
+    pub weight: u64,
+}
+
+/// Checks the entries of `reminderTypes`.
+pub fn validate_reminder_types(types: &[ReminderType]) -> Result<(), String> {
+    types.iter().try_for_each(|reminder| {
+        if reminder.name.trim().is_empty() || reminder.message.trim().is_empty() {
+            Err("each reminder type needs a name and a message".to_string())
+        } else if reminder.weight == 0 || reminder.weight > 100 {
+            Err(format!("reminder type '{}' needs a weight between 1 and 100", reminder.name))
+        } else {
+            Ok(())
+        }
+    })
+}
+
+fn load_reminder_types(app: &AppHandle) -> Vec<ReminderType> {
+    crate::get_setting(app, "reminderTypes")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Draws an index into `weights` at random, in proportion to the weight at each index.
+pub fn weighted_index(weights: &[u64]) -> usize {
+    // Not worth a dependency: the clock's sub-second part is random enough to pick one.
+    let total: u64 = weights.iter().sum();
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| u64::from(d.subsec_nanos()))
+        .unwrap_or(0);
+    let mut roll = seed % total.max(1);
+    for (index, &weight) in weights.iter().enumerate() {
+        if roll < weight {
+            return index;
+        }
+        roll -= weight;
+    }
+    0
+}
+
+/// The reminder type for the next micro-break, or `None` if none are configured. With
+/// `advance` false (for previews) the round-robin position is left where it is.
+pub fn pick_reminder(app: &AppHandle, advance: bool) -> Option<ReminderType> {
+    let types = load_reminder_types(app);
+    if types.is_empty() {
+        return None;
+    }
+    if crate::get_string_setting(app, "reminderRotation", "round-robin") == "weighted" {
+        let weights: Vec<u64> = types.iter().map(|reminder| reminder.weight).collect();
+        return Some(types[weighted_index(&weights)].clone());
+    }
+
+    let store = app.store(SETTINGS_STORE).ok()?;
+    let index = match store.get("lastReminderIndex").and_then(|v| v.as_u64()) {
+        Some(last) => (last as usize + 1) % types.len(),
+        None => 0,
+    };
+    if advance {
+        store.set("lastReminderIndex", json!(index));
+        if let Err(e) = store.save() {
+            log::error!("❌ Failed to save lastReminderIndex: {}", e);
+        }
+    }
+    Some(types[index].clone())
+}
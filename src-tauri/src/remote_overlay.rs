@@ -0,0 +1,92 @@
+//! Optional remote break screen: with `allowExternalOverlay` on, the overlay loads the page at
+//! `overlayUrl` instead of the bundled `index.html`, so a team can share one break page.
+//!
+//! The remote page gets no access to the app's commands; breaks still end on the app's own
+//! timer. Before every break the page is fetched once, and if it can't be reached the
+//! bundled page is shown instead, so a down server never leaves a blank screen.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, WebviewUrl};
+use tauri_plugin_store::StoreExt;
+
+use crate::{BreakKind, SETTINGS_STORE};
+
+/// How long the remote page gets to answer before the bundled one is used.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `overlayUrl`, if external overlays are allowed and it is a usable http(s) URL.
+fn configured_url(app: &AppHandle) -> Option<reqwest::Url> {
+    if !crate::get_bool_setting(app, "allowExternalOverlay", false) {
+        return None;
+    }
+    let url = crate::get_string_setting(app, "overlayUrl", "");
+    let url = url.trim();
+    if url.is_empty() {
+        return None;
+    }
+    match reqwest::Url::parse(url) {
+        Ok(url) if matches!(url.scheme(), "http" | "https") => Some(url),
+        Ok(url) => {
+            log::warn!("⚠️ Ignoring overlayUrl with unsupported scheme '{}'", url.scheme());
+            None
+        }
+        Err(e) => {
+            log::warn!("⚠️ Ignoring invalid overlayUrl '{}': {}", url, e);
+            None
+        }
+    }
+}
+
+/// Whether `url` answers with a success status within [`PROBE_TIMEOUT`].
+async fn reachable(url: &reqwest::Url) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client.get(url.clone()).send().await.map_err(|e| e.to_string())?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("answered {}", response.status()))
+    }
+}
+
+/// The page the overlay for a `kind` break should load: the remote page when one is
+/// configured and reachable, otherwise the bundled one.
+pub async fn overlay_url(app: &AppHandle, kind: BreakKind) -> WebviewUrl {
+    let Some(url) = configured_url(app) else {
+        return kind.overlay_url();
+    };
+    match reachable(&url).await {
+        Ok(()) => {
+            log::info!("🌐 Loading the break screen from {}", url);
+            WebviewUrl::External(url)
+        }
+        Err(e) => {
+            log::warn!("⚠️ Remote break screen {} unavailable, using the bundled one: {}", url, e);
+            kind.overlay_url()
+        }
+    }
+}
+
+/// The configured `overlayUrl`, empty when none is set.
+#[tauri::command]
+pub fn get_overlay_url(app: AppHandle) -> String {
+    log::debug!("🌐 get_overlay_url command received!");
+    crate::get_string_setting(&app, "overlayUrl", "")
+}
+
+/// Sets `overlayUrl` to an http(s) URL, or clears it with an empty string. The page is only
+/// used while `allowExternalOverlay` is on.
+#[tauri::command]
+pub fn set_overlay_url(app: AppHandle, url: String) -> Result<(), String> {
+    log::debug!("🌐 set_overlay_url command received ({})", url);
+    let url = url.trim();
+    crate::settings::validate_value("overlayUrl", &serde_json::json!(url))?;
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    store.set("overlayUrl", serde_json::json!(url));
+    store.save().map_err(|e| e.to_string())?;
+    log::info!("✅ overlayUrl set to '{}'", url);
+    Ok(())
+}
@@ -0,0 +1,171 @@
+//! Keeps the schedule honest across system sleep.
+//!
+//! The OS's suspend/resume notifications (see `power::watch_sleep`) say when the machine
+//! sleeps and wakes. Where those are missing, a watchdog notices sleep instead: it wakes
+//! every `WATCHDOG_PERIOD` and compares how much wall-clock time passed with how much it
+//! slept for. Timers built on the monotonic clock come out of a suspend either late (the
+//! clock stopped) or all at once (it didn't), so on resume the break loop is restarted from
+//! the wall-clock time the next break was due, which the watchdog keeps in
+//! `Timer::next_break_at`.
+//!
+//! With `skipMissedBreaks`, a break that fell due more than `missedBreakThresholdMinutes`
+//! before waking is dropped instead: it is recorded as missed and a fresh interval starts.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use tauri::{AppHandle, Manager};
+use tokio::time::Instant;
+
+use crate::power::{self, SleepEvent};
+use crate::schedule::{Clock, SystemClock};
+use crate::{stats, BreakKind, TimerId, TimerState};
+
+const WATCHDOG_PERIOD: Duration = Duration::from_secs(10);
+/// A wall-clock jump bigger than this between two watchdog ticks is taken to be a sleep.
+const SLEEP_THRESHOLD: Duration = Duration::from_secs(30);
+/// A break that fell due during the sleep waits at least this long after waking.
+const RESUME_GRACE: Duration = Duration::from_secs(60);
+
+/// What the watchdog and the OS notifications know about the current sleep, shared so a
+/// wake-up is handled once by whichever notices it first.
+struct SleepWatch {
+    last_check: SystemTime,
+    /// Set by the OS's sleep notification until the matching wake-up is handled.
+    asleep_since: Option<SystemTime>,
+}
+
+pub fn start_watchdog(app: &AppHandle) {
+    let watch = Arc::new(Mutex::new(SleepWatch {
+        last_check: SystemTime::now(),
+        asleep_since: None,
+    }));
+
+    power::watch_sleep({
+        let app = app.clone();
+        let watch = watch.clone();
+        move |event| {
+            let mut watch = watch.lock().unwrap();
+            let now = SystemTime::now();
+            match event {
+                SleepEvent::Sleeping => {
+                    record_next_break(&app);
+                    watch.asleep_since = Some(now);
+                }
+                SleepEvent::Woke => {
+                    let Some(since) = watch.asleep_since.take() else {
+                        return;
+                    };
+                    watch.last_check = now;
+                    drop(watch);
+                    woke(&app, now.duration_since(since).unwrap_or_default());
+                }
+            }
+        }
+    });
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            // Asleep by the OS's account: the deadline is stale until the wake-up is handled.
+            if watch.lock().unwrap().asleep_since.is_none() {
+                record_next_break(&app);
+            }
+            tokio::time::sleep(WATCHDOG_PERIOD).await;
+            let gap = {
+                let mut watch = watch.lock().unwrap();
+                let now = SystemTime::now();
+                // An error means the clock went backwards, which is no sleep.
+                let gap = now.duration_since(watch.last_check).unwrap_or_default();
+                watch.last_check = now;
+                if gap <= WATCHDOG_PERIOD + SLEEP_THRESHOLD {
+                    continue;
+                }
+                watch.asleep_since = None;
+                gap
+            };
+            woke(&app, gap);
+        }
+    });
+}
+
+fn woke(app: &AppHandle, gap: Duration) {
+    log::info!("🌅 Woke up after about {:?} asleep", gap);
+    resync_after_sleep(app, gap);
+    crate::tray_icon::reset(app);
+}
+
+/// Stores when the next break is due as a wall-clock time, so it survives the monotonic
+/// clock misbehaving over a sleep.
+fn record_next_break(app: &AppHandle) {
+    let timer_state = app.state::<TimerState>();
+    let mut timer = timer_state.0.lock().unwrap();
+    for running in timer.loops.values_mut() {
+        running.next_break_at = Some(SystemTime::now() + running.deadline.saturating_duration_since(Instant::now()));
+    }
+}
+
+fn resync_after_sleep(app: &AppHandle, gap: Duration) {
+    let (due, kind) = {
+        let timer_state = app.state::<TimerState>();
+        let timer = timer_state.0.lock().unwrap();
+        if timer.paused || timer.focus_until.is_some() || timer.break_active {
+            return;
+        }
+        let due: Vec<(TimerId, Option<SystemTime>)> =
+            timer.loops.iter().map(|(id, running)| (id.clone(), running.next_break_at)).collect();
+        (due, timer.pending_break.unwrap_or(BreakKind::Micro))
+    };
+    for (id, next_break_at) in due {
+        let label = match &id {
+            TimerId::Regular => kind.as_str().to_string(),
+            TimerId::Named(name) => name.clone(),
+        };
+        resync_timer(app, id, next_break_at, &label, gap);
+    }
+}
+
+/// Brings the loop `id` back in line after a sleep of `gap`, given when its next break was
+/// due by the wall clock.
+fn resync_timer(app: &AppHandle, id: TimerId, next_break_at: Option<SystemTime>, label: &str, gap: Duration) {
+    // How long ago the next break fell due, if it did while asleep.
+    let overdue = next_break_at.and_then(|at| SystemTime::now().duration_since(at).ok());
+
+    let Some((config, _)) = crate::timer_schedule(app, &id, &SystemClock) else {
+        return;
+    };
+    // Asleep for longer than a whole interval counts as a break in itself.
+    if gap >= config.interval_at(SystemClock.local()) {
+        log::info!("💤 Away for longer than the interval of {}, starting a fresh one", id);
+        if id == TimerId::Regular {
+            app.state::<TimerState>().0.lock().unwrap().reset_work_clock();
+        }
+        if overdue.is_some() {
+            record_missed(app, label);
+        }
+        crate::start_timer(app, id);
+        return;
+    }
+    let threshold = Duration::from_secs(crate::get_u64_setting(app, "missedBreakThresholdMinutes", 5) * 60);
+    if let Some(overdue) = overdue {
+        if crate::get_bool_setting(app, "skipMissedBreaks", false) && overdue > threshold {
+            log::info!("⏭️ Break fell due {:?} before waking, skipping it as missed", overdue);
+            record_missed(app, label);
+            crate::start_timer(app, id);
+            return;
+        }
+    }
+    let Some(next_break_at) = next_break_at else {
+        return;
+    };
+    let remaining = next_break_at.duration_since(SystemTime::now()).unwrap_or_default();
+    let delay = remaining.max(RESUME_GRACE);
+    log::info!("⏰ Rescheduling the next break of {} for {:?} from now", id, delay);
+    crate::spawn_timer_loop(app, id, delay, &SystemClock);
+}
+
+fn record_missed(app: &AppHandle, kind: &str) {
+    if let Err(e) = stats::record_break(app, kind, stats::BreakOutcome::Missed, Duration::ZERO) {
+        log::error!("❌ Failed to record missed break: {}", e);
+    }
+}
@@ -0,0 +1,732 @@
+//! The break scheduler: when the next break is due and whether it may show.
+//!
+//! `SchedulerConfig` gathers the settings a schedule is built from, and `next_break_after`
+//! works out the next break from them under the `ScheduleRules` (work hours,
+//! do-not-disturb windows, the end of the workday) and the daily quota. The timer loops and
+//! `upcoming_breaks` both go through it, reading the time from a `Clock` so the decisions
+//! can be checked against any moment. `breaks_per_day_estimate` sizes up a schedule for
+//! the settings window.
+//!
+//! Pomodoro mode replaces the plain break interval with explicit work and break phases.
+//! With `pomodoroMode` on, the timer loop alternates work phases of `pomodoroWorkMinutes`
+//! with breaks of `pomodoroBreakMinutes`; after every `pomodoroLongBreakEvery` of those
+//! comes a long one of `pomodoroLongBreakMinutes`. The next work phase only starts once the
+//! break has ended, however it ended. Every phase change is announced with `phase-changed`.
+
+use std::time::Duration;
+
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::time::Instant;
+
+use crate::time_window::{self, TimeWindow};
+use crate::weekday::WeekdaySettings;
+use crate::{BreakKind, TimerState};
+
+/// Furthest ahead `upcoming_breaks` looks.
+const LOOKAHEAD_DAYS: u64 = 14;
+/// Most break times `upcoming_breaks` returns.
+const MAX_UPCOMING_BREAKS: u64 = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Phase {
+    Work,
+    Break,
+}
+
+impl Phase {
+    pub fn label(self) -> &'static str {
+        match self {
+            Phase::Work => "Work",
+            Phase::Break => "Break",
+        }
+    }
+}
+
+/// Payload of the app-wide `phase-changed` event.
+#[derive(Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PhaseChanged {
+    phase: Phase,
+    /// The kind of break, for break phases.
+    kind: Option<BreakKind>,
+    duration_seconds: u64,
+}
+
+pub fn pomodoro_mode(app: &AppHandle) -> bool {
+    crate::get_bool_setting(app, "pomodoroMode", false)
+}
+
+pub fn work_minutes(app: &AppHandle) -> u64 {
+    crate::get_u64_setting(app, "pomodoroWorkMinutes", 25)
+}
+
+pub fn break_seconds(app: &AppHandle, kind: BreakKind) -> u64 {
+    let minutes = match kind {
+        BreakKind::Micro => crate::get_u64_setting(app, "pomodoroBreakMinutes", 5),
+        BreakKind::Long | BreakKind::EndOfDay => crate::get_u64_setting(app, "pomodoroLongBreakMinutes", 15),
+    };
+    minutes * 60
+}
+
+/// Micro-breaks between long ones: `pomodoroLongBreakEvery` in pomodoro mode,
+/// `longBreakEveryNMicroBreaks` otherwise.
+pub fn long_break_every(app: &AppHandle) -> u64 {
+    if pomodoro_mode(app) {
+        crate::get_u64_setting(app, "pomodoroLongBreakEvery", 3)
+    } else {
+        let today = SystemClock.local().weekday();
+        WeekdaySettings::load(app).u64_on("longBreakEveryNMicroBreaks", today, 0)
+    }
+}
+
+/// One step of `frequencyRamp`: from `after_time` on, breaks come every `interval_minutes`.
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RampStep {
+    pub after_time: String,
+    pub interval_minutes: u64,
+}
+
+/// Steps must be in time order, each later than the one before, with a usable interval.
+pub fn validate_frequency_ramp(steps: &[RampStep]) -> Result<(), String> {
+    let mut previous: Option<NaiveTime> = None;
+    for step in steps {
+        let after = time_window::parse_hhmm(&step.after_time)?;
+        if !(1..=480).contains(&step.interval_minutes) {
+            return Err(format!("the interval after {} must be between 1 and 480 minutes", step.after_time));
+        }
+        if previous.is_some_and(|previous| after <= previous) {
+            return Err("ramp steps must be in time order".to_string());
+        }
+        previous = Some(after);
+    }
+    Ok(())
+}
+
+/// `frequencyRamp` as (start, minutes) pairs in time order.
+fn load_ramp(app: &AppHandle) -> Vec<(NaiveTime, u64)> {
+    let steps: Vec<RampStep> = crate::get_setting(app, "frequencyRamp")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+    steps
+        .iter()
+        .filter_map(|step| Some((time_window::parse_hhmm(&step.after_time).ok()?, step.interval_minutes)))
+        .collect()
+}
+
+/// Why a break that came due is held back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Blocked {
+    OutsideWorkHours,
+    DoNotDisturb,
+    /// The end-of-day break has been shown and `endOfDaySuppressBreaks` is on.
+    DayOver,
+}
+
+/// The clock-based rules deciding whether a due break may show: work hours, do-not-disturb
+/// windows and a finished workday. The timer loops and `upcoming_breaks` both go through these.
+pub struct ScheduleRules {
+    work: Option<TimeWindow>,
+    dnd: Vec<TimeWindow>,
+    /// Day the end-of-day break ended, if regular breaks stop after it.
+    day_over: Option<NaiveDate>,
+}
+
+impl ScheduleRules {
+    pub fn load(app: &AppHandle) -> Self {
+        Self::with_settings(app, &WeekdaySettings::load(app))
+    }
+
+    /// The rules with work hours from an already read `settings`.
+    pub fn with_settings(app: &AppHandle, settings: &WeekdaySettings) -> Self {
+        ScheduleRules {
+            work: crate::work_window(settings),
+            dnd: time_window::load_windows(app, "dndSchedules"),
+            day_over: crate::end_of_day::suppressed_day(app),
+        }
+    }
+
+    /// When work hours next begin after `at`, or `None` if `at` is inside them or there
+    /// are none.
+    pub fn work_start_after(&self, at: NaiveDateTime) -> Option<NaiveDateTime> {
+        let window = self.work.as_ref()?;
+        if window.contains(at) {
+            return None;
+        }
+        window.next_start(at)
+    }
+
+    pub fn in_dnd(&self, at: NaiveDateTime) -> bool {
+        self.dnd.iter().any(|window| window.contains(at))
+    }
+
+    pub fn blocked_at(&self, at: NaiveDateTime) -> Option<Blocked> {
+        if self.work_start_after(at).is_some() {
+            Some(Blocked::OutsideWorkHours)
+        } else if self.in_dnd(at) {
+            Some(Blocked::DoNotDisturb)
+        } else if self.day_over == Some(at.date()) {
+            Some(Blocked::DayOver)
+        } else {
+            None
+        }
+    }
+}
+
+/// Where the scheduler reads the time from. `SystemClock` in the app; a fixed clock lets
+/// the scheduling decisions be worked out for any moment. Everything that works out when a
+/// break is due takes one, down to the timer loops.
+pub trait Clock: Send + Sync {
+    /// Local wall-clock time, for work hours and do-not-disturb windows.
+    fn local(&self) -> NaiveDateTime;
+    /// Seconds since the Unix epoch, for the deadlines kept in the store.
+    fn unix_seconds(&self) -> u64;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn local(&self) -> NaiveDateTime {
+        chrono::Local::now().naive_local()
+    }
+
+    fn unix_seconds(&self) -> u64 {
+        chrono::Utc::now().timestamp() as u64
+    }
+}
+
+/// Everything the scheduler reads from the store and app state to decide when breaks are
+/// due, gathered up front so those decisions don't touch the app.
+pub struct SchedulerConfig {
+    /// `microBreakIntervalMinutes` as configured for today; 0 turns the timer off.
+    pub interval_minutes: u64,
+    /// The configured interval on each weekday, Monday first, `weekdayOverrides` included.
+    interval_minutes_by_day: [u64; 7],
+    /// `frequencyRamp` steps as (start, minutes), in time order.
+    ramp: Vec<(NaiveTime, u64)>,
+    /// `pomodoroWorkMinutes`, standing in for the interval in pomodoro mode.
+    pomodoro_work_minutes: Option<u64>,
+    /// What the battery and escalation adjustments multiply every interval by.
+    interval_scale: f64,
+    pub rules: ScheduleRules,
+    /// `maxBreaksPerDay`; 0 for no quota.
+    pub quota: u64,
+    /// `snoozedUntil`, a snooze or postponement carried over from before a restart.
+    pub snoozed_until: Option<u64>,
+    /// What is left of `startupGraceMinutes`.
+    pub startup_grace: Option<Duration>,
+}
+
+impl SchedulerConfig {
+    pub fn load(app: &AppHandle, clock: &dyn Clock) -> Self {
+        let mut interval_scale = 1.0;
+        if crate::get_bool_setting(app, "batterySaverProfile", false) && crate::power::on_battery() {
+            interval_scale *= crate::get_f64_setting(app, "batteryIntervalMultiplier", 1.5).max(1.0);
+        }
+        if crate::escalation_level(app) > 0 {
+            interval_scale *= crate::get_u64_setting(app, "escalationIntervalPercent", 50).clamp(1, 100) as f64 / 100.0;
+        }
+        let days = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun];
+        let settings = WeekdaySettings::load(app);
+        let interval_minutes_by_day = days.map(|day| crate::micro_break_interval_minutes_on(&settings, day));
+        SchedulerConfig {
+            interval_minutes: interval_minutes_by_day[clock.local().weekday().num_days_from_monday() as usize],
+            interval_minutes_by_day,
+            ramp: load_ramp(app),
+            pomodoro_work_minutes: pomodoro_mode(app).then(|| work_minutes(app)),
+            interval_scale,
+            rules: ScheduleRules::with_settings(app, &settings),
+            quota: crate::get_u64_setting(app, "maxBreaksPerDay", 0),
+            snoozed_until: Some(crate::get_u64_setting(app, "snoozedUntil", 0)).filter(|at| *at > 0),
+            startup_grace: crate::startup_grace_remaining(app),
+        }
+    }
+
+    /// The schedule of a named timer running every `minutes`: the same work hours,
+    /// do-not-disturb windows, quota and startup grace, without the regular schedule's
+    /// ramp, pomodoro phases, scaling or carried-over snooze.
+    pub fn for_timer(app: &AppHandle, clock: &dyn Clock, minutes: u64) -> Self {
+        SchedulerConfig {
+            interval_minutes: minutes,
+            interval_minutes_by_day: [minutes; 7],
+            ramp: Vec::new(),
+            pomodoro_work_minutes: None,
+            interval_scale: 1.0,
+            snoozed_until: None,
+            ..Self::load(app, clock)
+        }
+    }
+
+    /// The interval for a cycle starting at `at`: the work phase in pomodoro mode, else the
+    /// `frequencyRamp` step reached by then or that weekday's interval, then scaled for
+    /// battery and escalation.
+    pub fn interval_at(&self, at: NaiveDateTime) -> Duration {
+        let mut ramp = self.ramp.iter().filter(|(after, _)| *after <= at.time()).map(|(_, minutes)| *minutes);
+        let minutes = self
+            .pomodoro_work_minutes
+            .or_else(|| ramp.next_back())
+            .unwrap_or(self.interval_minutes_by_day[at.weekday().num_days_from_monday() as usize]);
+        Duration::from_secs(minutes * 60).mul_f64(self.interval_scale)
+    }
+
+    /// How much of a carried-over snooze is left at `clock`'s time.
+    pub fn snooze_remaining(&self, clock: &dyn Clock) -> Option<Duration> {
+        let at = self.snoozed_until?;
+        let now = clock.unix_seconds();
+        (at > now).then(|| Duration::from_secs(at - now))
+    }
+
+    /// When the first break is due: after any snooze still running, else a full interval,
+    /// but never inside the startup grace. `None` when the timer is off.
+    pub fn first_delay(&self, clock: &dyn Clock) -> Option<Duration> {
+        if self.interval_minutes == 0 {
+            return None;
+        }
+        let delay = self.snooze_remaining(clock).unwrap_or_else(|| self.interval_at(clock.local()));
+        Some(match self.startup_grace {
+            Some(grace) if grace > delay => grace,
+            _ => delay,
+        })
+    }
+
+    /// How long from `clock`'s time until the break of the cycle starting then is due; see
+    /// `next_break_after`.
+    pub fn time_to_next_break(&self, clock: &dyn Clock, delay: Option<Duration>, left_today: Option<u64>) -> Duration {
+        let now = clock.local();
+        (next_break_after(now, self, delay, left_today) - now).to_std().unwrap_or_default()
+    }
+}
+
+/// When the break of a cycle starting at `now` is due: after `delay` if one is given (a
+/// snooze, postponement or resumed deadline), otherwise a full interval on. Outside work
+/// hours the cycle waits for them to begin and once today's quota is used up (`left_today`
+/// of 0) for midnight, starting a full interval from then. The timer loop and
+/// `upcoming_breaks` both work the schedule out through this.
+pub fn next_break_after(
+    now: NaiveDateTime,
+    config: &SchedulerConfig,
+    delay: Option<Duration>,
+    left_today: Option<u64>,
+) -> NaiveDateTime {
+    if let Some(start) = config.rules.work_start_after(now) {
+        return start + config.interval_at(start);
+    }
+    if left_today == Some(0) {
+        let midnight = (now.date() + chrono::Days::new(1)).and_time(NaiveTime::MIN);
+        return next_break_after(midnight, config, None, None);
+    }
+    now + delay.unwrap_or_else(|| config.interval_at(now))
+}
+
+fn enter_phase(app: &AppHandle, phase: Phase, kind: Option<BreakKind>, length: Duration) {
+    log::info!("🍅 {} phase for {:?}", phase.label(), length);
+    {
+        let timer_state = app.state::<TimerState>();
+        let mut timer = timer_state.0.lock().unwrap();
+        timer.phase = Some(phase);
+        timer.set_deadline(&crate::TimerId::Regular, Instant::now() + length);
+    }
+    let changed = PhaseChanged {
+        phase,
+        kind,
+        duration_seconds: length.as_secs(),
+    };
+    if let Err(e) = app.emit("phase-changed", changed) {
+        log::error!("❌ Failed to emit phase-changed: {}", e);
+    }
+    crate::refresh_tray(app);
+}
+
+async fn wait_for_break_to_end(app: &AppHandle) {
+    while app.state::<TimerState>().0.lock().unwrap().break_active {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+    }
+}
+
+/// Runs work and break phases in turn until cancelled. The first work phase lasts
+/// `first_work`, so a resumed or snoozed schedule picks up where it was.
+pub async fn run(app: AppHandle, first_work: Duration, clock: &'static dyn Clock) {
+    let mut work = first_work;
+    loop {
+        enter_phase(&app, Phase::Work, None, work);
+        crate::wait_for_break(&app, work).await;
+        crate::events::publish(&app, crate::events::LifecycleEvent::BreakDue);
+        let config = SchedulerConfig::load(&app, clock);
+        let now = clock.local();
+        work = config.interval_at(now);
+
+        if app.state::<TimerState>().0.lock().unwrap().break_active {
+            log::info!("🎬 A break is already showing, starting the next work phase after it");
+            wait_for_break_to_end(&app).await;
+            continue;
+        }
+        match config.rules.blocked_at(now) {
+            Some(Blocked::OutsideWorkHours) => {
+                log::info!("🌙 Outside work hours, going straight on to the next work phase");
+                continue;
+            }
+            Some(Blocked::DoNotDisturb) => {
+                log::info!("🔕 Inside a do-not-disturb window, going straight on to the next work phase");
+                crate::record_auto_postponed(&app, &crate::TimerId::Regular);
+                continue;
+            }
+            Some(Blocked::DayOver) => {
+                log::info!("🌇 The workday is over, going straight on to the next work phase");
+                continue;
+            }
+            None => {}
+        }
+        if crate::breaks_left_today(&app) == Some(0) {
+            log::info!("🎯 Daily break quota reached, going straight on to the next work phase");
+            continue;
+        }
+
+        let long_every = long_break_every(&app);
+        let kind = {
+            let timer_state = app.state::<TimerState>();
+            let mut timer = timer_state.0.lock().unwrap();
+            match timer.pending_break.take() {
+                Some(kind) => kind,
+                None => crate::next_break_kind(&mut timer.micro_breaks_since_long, long_every),
+            }
+        };
+        let length = Duration::from_secs(crate::break_duration_seconds(&app, kind));
+        enter_phase(&app, Phase::Break, Some(kind), length);
+        crate::show_overlay_window(&app, kind).await;
+        wait_for_break_to_end(&app).await;
+    }
+}
+
+/// How many breaks an `interval_minutes` schedule gives on `day`: the minutes of work hours
+/// (`work`, start and end; `None` for the whole day) outside the `dnd` windows, divided by
+/// the interval. An interval of 0 turns the timer off and gives 0. Work hours that end
+/// before they start run past midnight, as `TimeWindow` has them, and ending when they
+/// start makes them the whole day.
+pub fn estimate_breaks_per_day(
+    interval_minutes: u64,
+    work: Option<(NaiveTime, NaiveTime)>,
+    dnd: &[TimeWindow],
+    day: NaiveDate,
+) -> f64 {
+    if interval_minutes == 0 {
+        return 0.0;
+    }
+    let (start, end) = work.unwrap_or((NaiveTime::MIN, NaiveTime::MIN));
+    let start = day.and_time(start);
+    let end = if end > start.time() { day.and_time(end) } else { (day + chrono::Days::new(1)).and_time(end) };
+    let minutes = (end - start).num_minutes();
+    let open_minutes = (0..minutes)
+        .map(|minute| start + chrono::Duration::minutes(minute))
+        .filter(|at| !dnd.iter().any(|window| window.contains(*at)))
+        .count();
+    open_minutes as f64 / interval_minutes as f64
+}
+
+/// Estimated breaks per workday for the settings window, from the interval and work hours
+/// being edited (empty for none) and the stored do-not-disturb windows, as they fall today.
+#[tauri::command]
+pub fn breaks_per_day_estimate(
+    app: AppHandle,
+    interval_minutes: u64,
+    work_start: String,
+    work_end: String,
+) -> Result<f64, String> {
+    log::debug!("🧮 breaks_per_day_estimate command received ({} minutes, {}-{})", interval_minutes, work_start, work_end);
+    let work = if work_start.trim().is_empty() || work_end.trim().is_empty() {
+        None
+    } else {
+        Some((time_window::parse_hhmm(&work_start)?, time_window::parse_hhmm(&work_end)?))
+    };
+    let dnd = time_window::load_windows(&app, "dndSchedules");
+    Ok(estimate_breaks_per_day(interval_minutes, work, &dnd, chrono::Local::now().date_naive()))
+}
+
+/// The next `count` times a break is due to show, as local ISO timestamps. Walks the
+/// schedule forward from the current deadline through the same work-hours, do-not-disturb
+/// and daily-quota rules the timer applies, assuming every break is taken. Empty while
+/// paused or while no timer is running.
+#[tauri::command]
+pub fn upcoming_breaks(app: AppHandle, count: u64) -> Vec<String> {
+    log::debug!("📅 upcoming_breaks command received! count: {}", count);
+    upcoming_breaks_from(&app, count, &SystemClock)
+}
+
+fn upcoming_breaks_from(app: &AppHandle, count: u64, clock: &dyn Clock) -> Vec<String> {
+    let now = clock.local();
+    let config = SchedulerConfig::load(app, clock);
+    let interval = config.interval_at(now);
+    let (first_due, mut micro_since_long, mut pending) = {
+        let timer_state = app.state::<TimerState>();
+        let timer = timer_state.0.lock().unwrap();
+        let until = |at: Instant| at.saturating_duration_since(Instant::now());
+        let first_due = if timer.paused {
+            None
+        } else if let Some(focus_until) = timer.focus_until {
+            Some(now + until(focus_until) + interval)
+        } else if let Some(deadline) = timer.deadline(&crate::TimerId::Regular) {
+            // A pomodoro break phase ends at the deadline; the next break follows a full
+            // work phase later.
+            let extra = if timer.phase == Some(Phase::Break) { interval } else { Duration::ZERO };
+            Some(now + until(deadline) + extra)
+        } else {
+            None
+        };
+        (first_due, timer.micro_breaks_since_long, timer.pending_break)
+    };
+    let Some(mut due) = first_due else {
+        return Vec::new();
+    };
+
+    let pomodoro = config.pomodoro_work_minutes.is_some();
+    let long_every = long_break_every(app);
+    let mut left_today = crate::breaks_left_today(app);
+    let mut quota_day = now.date();
+    let horizon = now + chrono::Days::new(LOOKAHEAD_DAYS);
+    let count = count.min(MAX_UPCOMING_BREAKS) as usize;
+    let mut breaks = Vec::new();
+
+    while breaks.len() < count && due < horizon {
+        if config.quota > 0 && due.date() != quota_day {
+            quota_day = due.date();
+            left_today = Some(config.quota);
+        }
+        let mut cycle_start = due;
+        if config.rules.blocked_at(due).is_none() && left_today != Some(0) {
+            let kind = pending
+                .take()
+                .unwrap_or_else(|| crate::next_break_kind(&mut micro_since_long, long_every));
+            breaks.push(due.format("%Y-%m-%dT%H:%M:%S").to_string());
+            if let Some(left) = left_today.as_mut() {
+                *left -= 1;
+            }
+            if pomodoro {
+                // The next work phase starts once the break is over.
+                cycle_start += Duration::from_secs(crate::break_duration_seconds(app, kind));
+            }
+        }
+        // Pomodoro mode goes straight on to the next work phase whatever held a break back.
+        due = if pomodoro {
+            cycle_start + config.interval_at(cycle_start)
+        } else {
+            next_break_after(cycle_start, &config, None, left_today)
+        };
+    }
+    breaks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: Duration = Duration::from_secs(60);
+
+    struct FixedClock(NaiveDateTime);
+
+    impl Clock for FixedClock {
+        fn local(&self) -> NaiveDateTime {
+            self.0
+        }
+
+        fn unix_seconds(&self) -> u64 {
+            self.0.and_utc().timestamp() as u64
+        }
+    }
+
+    /// 2026-10-16 is a Friday.
+    fn at(text: &str) -> FixedClock {
+        FixedClock(NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap())
+    }
+
+    fn window(start: &str, end: &str, days: &[Weekday]) -> TimeWindow {
+        TimeWindow {
+            start: time_window::parse_hhmm(start).unwrap(),
+            end: time_window::parse_hhmm(end).unwrap(),
+            days: days.to_vec(),
+        }
+    }
+
+    /// A break every 20 minutes at any hour, with no quota.
+    fn every_20_minutes() -> SchedulerConfig {
+        SchedulerConfig {
+            interval_minutes: 20,
+            interval_minutes_by_day: [20; 7],
+            ramp: Vec::new(),
+            pomodoro_work_minutes: None,
+            interval_scale: 1.0,
+            rules: ScheduleRules {
+                work: None,
+                dnd: Vec::new(),
+                day_over: None,
+            },
+            quota: 0,
+            snoozed_until: None,
+            startup_grace: None,
+        }
+    }
+
+    #[test]
+    fn a_break_is_due_an_interval_or_the_given_delay_from_now() {
+        let config = every_20_minutes();
+        let clock = at("2026-10-16 10:00");
+        assert_eq!(config.time_to_next_break(&clock, None, None), 20 * MINUTE);
+        assert_eq!(config.time_to_next_break(&clock, Some(5 * MINUTE), None), 5 * MINUTE);
+        assert_eq!(config.first_delay(&clock), Some(20 * MINUTE));
+    }
+
+    #[test]
+    fn the_first_break_honours_a_carried_over_snooze_and_the_startup_grace() {
+        let clock = at("2026-10-16 10:00");
+        let config = SchedulerConfig {
+            snoozed_until: Some(clock.unix_seconds() + 300),
+            ..every_20_minutes()
+        };
+        assert_eq!(config.first_delay(&clock), Some(5 * MINUTE));
+
+        let config = SchedulerConfig {
+            startup_grace: Some(30 * MINUTE),
+            ..every_20_minutes()
+        };
+        assert_eq!(config.first_delay(&clock), Some(30 * MINUTE));
+
+        let off = SchedulerConfig {
+            interval_minutes: 0,
+            ..every_20_minutes()
+        };
+        assert_eq!(off.first_delay(&clock), None);
+    }
+
+    #[test]
+    fn the_interval_follows_the_ramp_the_weekday_and_the_scale() {
+        let mut interval_minutes_by_day = [20; 7];
+        interval_minutes_by_day[Weekday::Sat.num_days_from_monday() as usize] = 60;
+        let config = SchedulerConfig {
+            interval_minutes_by_day,
+            ramp: vec![(time_window::parse_hhmm("14:00").unwrap(), 10)],
+            interval_scale: 1.5,
+            ..every_20_minutes()
+        };
+        assert_eq!(config.time_to_next_break(&at("2026-10-16 10:00"), None, None), 30 * MINUTE);
+        assert_eq!(config.time_to_next_break(&at("2026-10-16 15:00"), None, None), 15 * MINUTE);
+        assert_eq!(config.time_to_next_break(&at("2026-10-17 10:00"), None, None), 90 * MINUTE);
+    }
+
+    #[test]
+    fn do_not_disturb_holds_breaks_back_across_midnight() {
+        let config = SchedulerConfig {
+            rules: ScheduleRules {
+                dnd: vec![window("22:00", "07:00", &[Weekday::Fri])],
+                ..every_20_minutes().rules
+            },
+            ..every_20_minutes()
+        };
+        let blocked = |clock: FixedClock| config.rules.blocked_at(clock.local());
+        assert_eq!(blocked(at("2026-10-16 21:59")), None);
+        assert_eq!(blocked(at("2026-10-16 23:00")), Some(Blocked::DoNotDisturb));
+        assert_eq!(blocked(at("2026-10-17 06:59")), Some(Blocked::DoNotDisturb));
+        assert_eq!(blocked(at("2026-10-17 07:00")), None);
+        // Only Friday's window: Saturday night is free.
+        assert_eq!(blocked(at("2026-10-17 23:00")), None);
+        // The break still falls due on time; it is the loop that holds it back.
+        assert_eq!(config.time_to_next_break(&at("2026-10-16 23:00"), None, None), 20 * MINUTE);
+    }
+
+    #[test]
+    fn outside_work_hours_the_next_break_follows_their_start() {
+        let weekdays = [Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri];
+        let config = SchedulerConfig {
+            rules: ScheduleRules {
+                work: Some(window("09:00", "17:00", &weekdays)),
+                ..every_20_minutes().rules
+            },
+            ..every_20_minutes()
+        };
+        let due = |clock: FixedClock, delay| {
+            let now = clock.local();
+            next_break_after(now, &config, delay, None)
+        };
+        assert_eq!(due(at("2026-10-16 07:00"), None), at("2026-10-16 09:20").local());
+        // A snooze carried into the morning is dropped for a full interval.
+        assert_eq!(due(at("2026-10-16 07:00"), Some(MINUTE)), at("2026-10-16 09:20").local());
+        assert_eq!(due(at("2026-10-16 10:00"), None), at("2026-10-16 10:20").local());
+        // Friday evening waits for Monday.
+        assert_eq!(due(at("2026-10-16 18:00"), None), at("2026-10-19 09:20").local());
+        assert_eq!(config.rules.blocked_at(at("2026-10-17 10:00").local()), Some(Blocked::OutsideWorkHours));
+    }
+
+    #[test]
+    fn a_used_up_quota_starts_the_next_day_with_its_own_interval() {
+        let mut interval_minutes_by_day = [20; 7];
+        interval_minutes_by_day[Weekday::Sat.num_days_from_monday() as usize] = 60;
+        let config = SchedulerConfig {
+            interval_minutes_by_day,
+            quota: 4,
+            ..every_20_minutes()
+        };
+        let clock = at("2026-10-16 15:00");
+        assert_eq!(config.time_to_next_break(&clock, None, Some(1)), 20 * MINUTE);
+        assert_eq!(
+            next_break_after(clock.local(), &config, None, Some(0)),
+            at("2026-10-17 01:00").local()
+        );
+
+        let with_work_hours = SchedulerConfig {
+            rules: ScheduleRules {
+                work: Some(window("09:00", "17:00", &[])),
+                ..every_20_minutes().rules
+            },
+            ..config
+        };
+        assert_eq!(
+            next_break_after(clock.local(), &with_work_hours, None, Some(0)),
+            at("2026-10-17 10:00").local()
+        );
+    }
+
+    fn hours(start: &str, end: &str) -> Option<(NaiveTime, NaiveTime)> {
+        Some((time_window::parse_hhmm(start).unwrap(), time_window::parse_hhmm(end).unwrap()))
+    }
+
+    /// 2026-10-16 is a Friday.
+    fn friday() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 10, 16).unwrap()
+    }
+
+    #[test]
+    fn an_interval_of_zero_estimates_no_breaks() {
+        assert_eq!(estimate_breaks_per_day(0, hours("09:00", "17:00"), &[], friday()), 0.0);
+        assert_eq!(estimate_breaks_per_day(0, None, &[], friday()), 0.0);
+    }
+
+    #[test]
+    fn estimates_work_hours_divided_by_the_interval() {
+        assert_eq!(estimate_breaks_per_day(20, hours("09:00", "17:00"), &[], friday()), 24.0);
+        assert_eq!(estimate_breaks_per_day(60, None, &[], friday()), 24.0);
+    }
+
+    #[test]
+    fn work_hours_ending_before_they_start_run_past_midnight() {
+        assert_eq!(estimate_breaks_per_day(30, hours("22:00", "06:00"), &[], friday()), 16.0);
+        // Ending when they start is the whole day.
+        assert_eq!(estimate_breaks_per_day(60, hours("08:00", "08:00"), &[], friday()), 24.0);
+    }
+
+    #[test]
+    fn dnd_windows_take_their_minutes_out_of_the_estimate() {
+        let lunch = [window("12:00", "13:00", &[Weekday::Fri])];
+        assert_eq!(estimate_breaks_per_day(20, hours("09:00", "17:00"), &lunch, friday()), 21.0);
+        // Only on the days the window applies.
+        let saturday = friday() + chrono::Days::new(1);
+        assert_eq!(estimate_breaks_per_day(20, hours("09:00", "17:00"), &lunch, saturday), 24.0);
+    }
+
+    #[test]
+    fn dnd_windows_crossing_midnight_count_on_both_sides_of_it() {
+        // 23:00 to 01:00 every day: the first hour of the day and the last.
+        let night = [window("23:00", "01:00", &[])];
+        assert_eq!(estimate_breaks_per_day(60, None, &night, friday()), 22.0);
+        // Night-shift hours lose the two hours in the middle.
+        assert_eq!(estimate_breaks_per_day(60, hours("20:00", "04:00"), &night, friday()), 6.0);
+    }
+}
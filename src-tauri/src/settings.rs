@@ -0,0 +1,559 @@
+//! The settings stored in `settings.json`: which keys exist and what values they accept.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde_json::{json, Map, Value};
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::Shortcut;
+use tauri_plugin_store::{Store, StoreExt};
+
+use crate::time_window::{self, TimeWindowSetting};
+use crate::SETTINGS_STORE;
+
+enum Rule {
+    Bool,
+    /// Whole number within an inclusive range.
+    Number { min: u64, max: u64 },
+    /// Number from 0.0 to 1.0.
+    Fraction,
+    /// Number within an inclusive range, fractions allowed.
+    Decimal { min: f64, max: f64 },
+    Text,
+    /// One of a fixed set of text values.
+    OneOf(&'static [&'static str]),
+    TextList,
+    /// http(s) URL, or empty for none.
+    Url,
+    /// Global shortcut accelerator, or empty for none.
+    Hotkey,
+    /// "HH:MM", or empty for none.
+    Time,
+    Weekdays,
+    /// Map of weekday name to the schedule settings that day overrides.
+    WeekdayOverrides,
+    TimeWindows,
+    /// Map of profile name to the settings that profile applies.
+    Profiles,
+    /// List of `{profile, start, end, days}` entries, highest priority first.
+    ProfileSchedules,
+    /// List of `{text, weight, categories}` messages, or plain strings.
+    BreakMessages,
+    /// List of `{name, icon, message, weight}` reminder types.
+    ReminderTypes,
+    /// List of `{afterTime, intervalMinutes}` steps, in time order.
+    FrequencyRamp,
+    /// List of `{name, intervalMinutes, durationSeconds, icon, message}` extra timers.
+    Timers,
+    /// "all", or a list of monitor names.
+    Monitors,
+}
+
+/// What a setting starts out as before the user changes it.
+enum Initial {
+    /// Not stored, so readers fall back to an older key or their own default.
+    Unset,
+    Bool(bool),
+    Number(u64),
+    Decimal(f64),
+    Text(&'static str),
+    EmptyList,
+    EmptyMap,
+    /// The OS locale's language, if there is a translation for it.
+    OsLanguage,
+}
+
+impl Initial {
+    fn value(&self) -> Option<Value> {
+        Some(match self {
+            Initial::Unset => return None,
+            Initial::Bool(value) => json!(value),
+            Initial::Number(value) => json!(value),
+            Initial::Decimal(value) => json!(value),
+            Initial::Text(value) => json!(value),
+            Initial::EmptyList => json!([]),
+            Initial::EmptyMap => json!({}),
+            Initial::OsLanguage => json!(crate::locale::detect_language()),
+        })
+    }
+}
+
+/// Every user-facing setting, the values it accepts and its default.
+const RULES: &[(&str, Rule, Initial)] = &[
+    ("intervalMinutes", Rule::Number { min: 1, max: 480 }, Initial::Number(20)),
+    ("overlayDurationSeconds", Rule::Number { min: 5, max: 600 }, Initial::Number(30)),
+    ("microBreakIntervalMinutes", Rule::Number { min: 1, max: 480 }, Initial::Unset),
+    ("frequencyRamp", Rule::FrequencyRamp, Initial::EmptyList),
+    ("timers", Rule::Timers, Initial::EmptyList),
+    ("microBreakDurationSeconds", Rule::Number { min: 5, max: 600 }, Initial::Unset),
+    ("longBreakEveryNMicroBreaks", Rule::Number { min: 0, max: 100 }, Initial::Number(0)),
+    ("longBreakDurationSeconds", Rule::Number { min: 5, max: 3600 }, Initial::Number(300)),
+    ("pomodoroMode", Rule::Bool, Initial::Bool(false)),
+    ("pomodoroWorkMinutes", Rule::Number { min: 1, max: 240 }, Initial::Number(25)),
+    ("pomodoroBreakMinutes", Rule::Number { min: 1, max: 60 }, Initial::Number(5)),
+    ("pomodoroLongBreakMinutes", Rule::Number { min: 1, max: 120 }, Initial::Number(15)),
+    ("pomodoroLongBreakEvery", Rule::Number { min: 0, max: 20 }, Initial::Number(3)),
+    ("maxBreakDurationSeconds", Rule::Number { min: 5, max: 7200 }, Initial::Number(1800)),
+    ("autoStart", Rule::Bool, Initial::Bool(false)),
+    ("maxSnoozesPerBreak", Rule::Number { min: 0, max: 20 }, Initial::Number(3)),
+    ("maxPostponements", Rule::Number { min: 0, max: 20 }, Initial::Number(3)),
+    ("idleResetThresholdSeconds", Rule::Number { min: 0, max: 86_400 }, Initial::Number(300)),
+    ("startupGraceMinutes", Rule::Number { min: 0, max: 240 }, Initial::Number(0)),
+    ("skipMissedBreaks", Rule::Bool, Initial::Bool(false)),
+    ("historyRetentionDays", Rule::Number { min: 0, max: 3650 }, Initial::Number(90)),
+    ("missedBreakThresholdMinutes", Rule::Number { min: 0, max: 1440 }, Initial::Number(5)),
+    ("triggerHotkey", Rule::Hotkey, Initial::Text(crate::hotkeys::DEFAULT_TRIGGER_HOTKEY)),
+    ("dismissHotkey", Rule::Hotkey, Initial::Text("")),
+    ("emergencyHotkey", Rule::Hotkey, Initial::Text(crate::hotkeys::DEFAULT_EMERGENCY_HOTKEY)),
+    ("settingsHotkey", Rule::Hotkey, Initial::Text(crate::hotkeys::DEFAULT_SETTINGS_HOTKEY)),
+    ("allowEarlyDismiss", Rule::Bool, Initial::Bool(true)),
+    ("requireBreakConfirmation", Rule::Bool, Initial::Bool(false)),
+    ("strictMode", Rule::Bool, Initial::Bool(false)),
+    ("escalateAfterSkips", Rule::Number { min: 0, max: 100 }, Initial::Number(0)),
+    ("escalationIntervalPercent", Rule::Number { min: 10, max: 100 }, Initial::Number(50)),
+    ("escalationStrict", Rule::Bool, Initial::Bool(true)),
+    ("acknowledgeKey", Rule::Text, Initial::Text(crate::DEFAULT_ACKNOWLEDGE_KEY)),
+    ("confirmBeforeExit", Rule::Bool, Initial::Bool(false)),
+    ("trayLeftClickAction", Rule::OneOf(&["open-settings", "break-now", "toggle-pause", "show-status"]), Initial::Text("open-settings")),
+    ("language", Rule::OneOf(crate::locale::LANGUAGES), Initial::OsLanguage),
+    ("resetOnManualBreak", Rule::Bool, Initial::Bool(false)),
+    ("pauseDurationWhenUnfocused", Rule::Bool, Initial::Bool(false)),
+    ("breakMessages", Rule::BreakMessages, Initial::EmptyList),
+    ("confirmationPrompts", Rule::TextList, Initial::EmptyList),
+    ("reminderTypes", Rule::ReminderTypes, Initial::EmptyList),
+    ("reminderRotation", Rule::OneOf(&["round-robin", "weighted"]), Initial::Text("round-robin")),
+    ("dndSchedules", Rule::TimeWindows, Initial::EmptyList),
+    ("maxBreaksPerDay", Rule::Number { min: 0, max: 1000 }, Initial::Number(0)),
+    ("preBreakWarningSeconds", Rule::Number { min: 0, max: 600 }, Initial::Number(30)),
+    ("workStartTime", Rule::Time, Initial::Text("")),
+    ("workEndTime", Rule::Time, Initial::Text("")),
+    ("workDays", Rule::Weekdays, Initial::EmptyList),
+    ("weekdayOverrides", Rule::WeekdayOverrides, Initial::EmptyMap),
+    ("endOfDayBreak", Rule::Bool, Initial::Bool(false)),
+    ("endOfDayBreakDurationSeconds", Rule::Number { min: 5, max: 3600 }, Initial::Number(300)),
+    ("endOfDayBreakMessage", Rule::Text, Initial::Text("")),
+    ("endOfDaySuppressBreaks", Rule::Bool, Initial::Bool(true)),
+    ("skipDuringFullscreen", Rule::Bool, Initial::Bool(false)),
+    ("fullscreenRetrySeconds", Rule::Number { min: 5, max: 3600 }, Initial::Number(60)),
+    ("fullscreenMaxRetries", Rule::Number { min: 0, max: 100 }, Initial::Number(5)),
+    ("waitForIdleToShow", Rule::Bool, Initial::Bool(false)),
+    ("idleToShowSeconds", Rule::Number { min: 1, max: 60 }, Initial::Number(3)),
+    ("maxDeferSeconds", Rule::Number { min: 0, max: 3600 }, Initial::Number(60)),
+    ("breakStartSound", Rule::Text, Initial::Text("")),
+    ("breakEndSound", Rule::Text, Initial::Text("")),
+    ("volume", Rule::Number { min: 0, max: 100 }, Initial::Number(70)),
+    ("overlayOpacity", Rule::Fraction, Initial::Decimal(1.0)),
+    ("batterySaverProfile", Rule::Bool, Initial::Bool(false)),
+    ("batteryIntervalMultiplier", Rule::Decimal { min: 1.0, max: 10.0 }, Initial::Decimal(1.5)),
+    ("overlayAlwaysOnTop", Rule::Bool, Initial::Bool(true)),
+    ("overlayFadeInMs", Rule::Number { min: 0, max: 5000 }, Initial::Number(300)),
+    ("overlayFadeOutMs", Rule::Number { min: 0, max: 5000 }, Initial::Number(300)),
+    ("minimumBreakSeconds", Rule::Number { min: 0, max: 600 }, Initial::Number(0)),
+    ("overlayCreateRetries", Rule::Number { min: 0, max: 10 }, Initial::Number(2)),
+    ("overlayBackgroundImage", Rule::Text, Initial::Text("")),
+    ("overlayTheme", Rule::OneOf(&["light", "dark", "auto"]), Initial::Text("dark")),
+    ("animatedTrayDuringBreak", Rule::Bool, Initial::Bool(false)),
+    ("overlayBlur", Rule::Bool, Initial::Bool(false)),
+    ("accessibilityMode", Rule::Bool, Initial::Bool(false)),
+    ("primaryContentOnly", Rule::Bool, Initial::Bool(false)),
+    ("overlayUrl", Rule::Url, Initial::Text("")),
+    ("allowExternalOverlay", Rule::Bool, Initial::Bool(false)),
+    ("overlayMode", Rule::OneOf(&["fullscreen", "windowed", "corner-toast"]), Initial::Text("fullscreen")),
+    ("overlayWidth", Rule::Number { min: 200, max: 7680 }, Initial::Number(800)),
+    ("overlayHeight", Rule::Number { min: 150, max: 4320 }, Initial::Number(600)),
+    ("overlayMonitors", Rule::Monitors, Initial::Text("all")),
+    ("overlayMonitorPreference", Rule::Text, Initial::Unset),
+    ("webhookUrl", Rule::Url, Initial::Text("")),
+    ("enableLocalControlSocket", Rule::Bool, Initial::Bool(false)),
+    ("allowShellHooks", Rule::Bool, Initial::Bool(false)),
+    ("onBreakEndCommand", Rule::Text, Initial::Text("")),
+    ("profiles", Rule::Profiles, Initial::Unset),
+    ("profileSchedules", Rule::ProfileSchedules, Initial::Unset),
+    ("activeProfile", Rule::Text, Initial::Unset),
+];
+
+/// Runtime state that lives in the settings store but isn't a user setting, so it is
+/// neither exported nor accepted on import.
+const INTERNAL_KEYS: &[&str] = &[
+    "paused",
+    "pausedRemainingSeconds",
+    "pausedUntil",
+    "lastMessageIndex",
+    "lastReminderIndex",
+    "snoozedUntil",
+    "focusUntil",
+    "snoozedUntilTomorrow",
+    "endOfDayBreakDate",
+    "shownTrayHint",
+    "oneOffBreaks",
+];
+
+fn check(rule: &Rule, value: &Value) -> Result<(), String> {
+    match rule {
+        Rule::Bool => value.as_bool().map(|_| ()).ok_or_else(|| "must be true or false".to_string()),
+        Rule::Number { min, max } => match value.as_u64() {
+            Some(n) if (*min..=*max).contains(&n) => Ok(()),
+            _ => Err(format!("must be a whole number between {} and {}", min, max)),
+        },
+        Rule::Fraction => match value.as_f64() {
+            Some(n) if (0.0..=1.0).contains(&n) => Ok(()),
+            _ => Err("must be a number between 0 and 1".to_string()),
+        },
+        Rule::Decimal { min, max } => match value.as_f64() {
+            Some(n) if (*min..=*max).contains(&n) => Ok(()),
+            _ => Err(format!("must be a number between {} and {}", min, max)),
+        },
+        Rule::Monitors => match value {
+            Value::String(all) if all == "all" => Ok(()),
+            _ => serde_json::from_value::<Vec<String>>(value.clone())
+                .map(|_| ())
+                .map_err(|_| "must be \"all\" or a list of monitor names".to_string()),
+        },
+        Rule::Text => value.as_str().map(|_| ()).ok_or_else(|| "must be text".to_string()),
+        Rule::OneOf(options) => match value.as_str() {
+            Some(text) if options.contains(&text) => Ok(()),
+            _ => Err(format!("must be one of: {}", options.join(", "))),
+        },
+        Rule::TextList => serde_json::from_value::<Vec<String>>(value.clone())
+            .map(|_| ())
+            .map_err(|_| "must be a list of text".to_string()),
+        Rule::Url => match value.as_str() {
+            Some("") => Ok(()),
+            Some(url) => match reqwest::Url::parse(url.trim()) {
+                Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+                Ok(_) => Err("must be an http or https URL".to_string()),
+                Err(e) => Err(format!("is not a valid URL: {}", e)),
+            },
+            None => Err("must be text".to_string()),
+        },
+        Rule::Hotkey => match value.as_str() {
+            Some("") => Ok(()),
+            Some(accelerator) => accelerator
+                .parse::<Shortcut>()
+                .map(|_| ())
+                .map_err(|e| format!("is not a valid shortcut: {}", e)),
+            None => Err("must be text".to_string()),
+        },
+        Rule::Time => match value.as_str() {
+            Some("") => Ok(()),
+            Some(time) => time_window::parse_hhmm(time).map(|_| ()),
+            None => Err("must be an HH:MM time".to_string()),
+        },
+        Rule::Weekdays => {
+            let days = serde_json::from_value::<Vec<String>>(value.clone())
+                .map_err(|_| "must be a list of weekdays".to_string())?;
+            days.iter().try_for_each(|day| time_window::parse_weekday(day).map(|_| ()))
+        }
+        Rule::TimeWindows => {
+            let windows = serde_json::from_value::<Vec<TimeWindowSetting>>(value.clone())
+                .map_err(|_| "must be a list of {start, end, days} entries".to_string())?;
+            windows
+                .iter()
+                .try_for_each(|window| time_window::TimeWindow::from_setting(window).map(|_| ()))
+        }
+        Rule::ProfileSchedules => {
+            let schedules = serde_json::from_value::<Vec<crate::profiles::ProfileSchedule>>(value.clone())
+                .map_err(|_| "must be a list of {profile, start, end, days} entries".to_string())?;
+            schedules.iter().try_for_each(|schedule| {
+                if schedule.profile.trim().is_empty() {
+                    return Err("each schedule must name a profile".to_string());
+                }
+                time_window::TimeWindow::from_setting(&schedule.window).map(|_| ())
+            })
+        }
+        Rule::BreakMessages => {
+            let messages = serde_json::from_value::<Vec<crate::messages::BreakMessage>>(value.clone())
+                .map_err(|_| "must be a list of messages or {text, weight, categories} entries".to_string())?;
+            crate::messages::validate_break_messages(&messages)
+        }
+        Rule::ReminderTypes => {
+            let types = serde_json::from_value::<Vec<crate::reminders::ReminderType>>(value.clone())
+                .map_err(|_| "must be a list of {name, icon, message, weight} entries".to_string())?;
+            crate::reminders::validate_reminder_types(&types)
+        }
+        Rule::FrequencyRamp => {
+            let steps = serde_json::from_value::<Vec<crate::schedule::RampStep>>(value.clone())
+                .map_err(|_| "must be a list of {afterTime, intervalMinutes} entries".to_string())?;
+            crate::schedule::validate_frequency_ramp(&steps)
+        }
+        Rule::Timers => {
+            let timers = serde_json::from_value::<Vec<crate::timers::TimerConfig>>(value.clone())
+                .map_err(|_| "must be a list of {name, intervalMinutes, durationSeconds, icon, message} entries".to_string())?;
+            crate::timers::validate_timers(&timers)
+        }
+        Rule::WeekdayOverrides => {
+            let overrides = value.as_object().ok_or_else(|| "must be a map of weekdays".to_string())?;
+            crate::weekday::validate_weekday_overrides(overrides)
+        }
+        Rule::Profiles => {
+            let profiles = value.as_object().ok_or_else(|| "must be a map of profiles".to_string())?;
+            profiles.iter().try_for_each(|(name, profile)| {
+                let profile = profile
+                    .as_object()
+                    .ok_or_else(|| format!("profile '{}' must be a map of settings", name))?;
+                crate::profiles::validate_profile_settings(profile)
+                    .map_err(|e| format!("profile '{}': {}", name, e))
+            })
+        }
+    }
+}
+
+/// Validates one setting, naming the key in the error.
+pub fn validate_value(key: &str, value: &Value) -> Result<(), String> {
+    let Some((_, rule, _)) = RULES.iter().find(|(name, _, _)| *name == key) else {
+        return Err(format!("Unknown setting '{}'", key));
+    };
+    check(rule, value).map_err(|e| format!("'{}' {}", key, e))
+}
+
+/// Like [`validate_value`], but accepts keys that have no rule (runtime state and the like).
+pub fn validate_known_value(key: &str, value: &Value) -> Result<(), String> {
+    match RULES.iter().find(|(name, _, _)| *name == key) {
+        Some((_, rule, _)) => check(rule, value).map_err(|e| format!("'{}' {}", key, e)),
+        None => Ok(()),
+    }
+}
+
+/// Brings `value` into the range the rule for `key` allows. Keys without a numeric range
+/// are returned unchanged.
+fn clamp_to_rule(key: &str, value: u64) -> u64 {
+    match RULES.iter().find(|(name, _, _)| *name == key) {
+        Some((_, Rule::Number { min, max }, _)) => value.clamp(*min, *max),
+        _ => value,
+    }
+}
+
+/// The micro-break keys fall back to the original `intervalMinutes`/`overlayDurationSeconds`,
+/// so a setter writes whichever of the pair is actually in effect.
+fn key_in_effect<R: tauri::Runtime>(store: &Store<R>, override_key: &'static str, key: &'static str) -> &'static str {
+    if store.get(override_key).is_some() {
+        override_key
+    } else {
+        key
+    }
+}
+
+/// Writes a number setting, clamped into its allowed range, and returns the stored value.
+fn set_number(app: &AppHandle, override_key: &'static str, key: &'static str, value: u64) -> Result<u64, String> {
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    let key = key_in_effect(&store, override_key, key);
+    let value = clamp_to_rule(key, value);
+    store.set(key, json!(value));
+    store.save().map_err(|e| e.to_string())?;
+    log::info!("✅ {} set to {}", key, value);
+    Ok(value)
+}
+
+/// Sets the minutes between micro-breaks and restarts the timer with it. Returns the value
+/// actually stored, which is clamped into the allowed range.
+#[tauri::command]
+pub fn set_interval(app: AppHandle, minutes: u64) -> Result<u64, String> {
+    log::debug!("⏱️ set_interval command received ({} minutes)", minutes);
+    let minutes = set_number(&app, "microBreakIntervalMinutes", "intervalMinutes", minutes)?;
+    crate::restart_timer(app).map_err(|problems| problems.join("; "))?;
+    Ok(minutes)
+}
+
+/// Sets how long micro-breaks last, from the next break on. Returns the value actually
+/// stored, which is clamped into the allowed range.
+#[tauri::command]
+pub fn set_duration(app: AppHandle, seconds: u64) -> Result<u64, String> {
+    log::debug!("⏱️ set_duration command received ({} seconds)", seconds);
+    set_number(&app, "microBreakDurationSeconds", "overlayDurationSeconds", seconds)
+}
+
+/// Sets how long micro-breaks last, like `set_duration`, and also stretches or shortens the
+/// micro-break on screen to match. Returns the value actually stored.
+#[tauri::command]
+pub fn set_current_and_default_duration(app: AppHandle, seconds: u64) -> Result<u64, String> {
+    log::debug!("⏱️ set_current_and_default_duration command received ({} seconds)", seconds);
+    let seconds = set_number(&app, "microBreakDurationSeconds", "overlayDurationSeconds", seconds)?;
+    crate::resize_current_break(&app, seconds);
+    Ok(seconds)
+}
+
+/// Checks every known setting currently in `store`, returning all problems found.
+pub fn validate_settings<R: tauri::Runtime>(store: &Store<R>) -> Result<(), Vec<String>> {
+    let problems: Vec<String> = RULES
+        .iter()
+        .filter_map(|(key, rule, _)| {
+            let value = store.get(*key)?;
+            check(rule, &value).err().map(|e| format!("'{}' {}", key, e))
+        })
+        .collect();
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
+/// Opens the settings store, first moving an unreadable `settings.json` aside to
+/// `settings.json.bak`. The store plugin would otherwise start empty and overwrite the
+/// broken file on the next save, losing whatever could still be rescued from it.
+pub fn open_settings_store(app: &AppHandle) -> Result<Arc<Store<tauri::Wry>>, String> {
+    let path = tauri_plugin_store::resolve_store_path(app, SETTINGS_STORE).map_err(|e| e.to_string())?;
+    if let Some(backup) = back_up_if_corrupt(&path)? {
+        log::warn!("⚠️ {} is corrupt, moved it to {} and starting from defaults", path.display(), backup.display());
+    }
+    app.store(SETTINGS_STORE).map_err(|e| e.to_string())
+}
+
+/// Moves the settings file at `path` to `<name>.json.bak` if it isn't a JSON object, and
+/// returns where it went. A missing file is fine and stays missing.
+fn back_up_if_corrupt(path: &Path) -> Result<Option<PathBuf>, String> {
+    let Ok(bytes) = std::fs::read(path) else {
+        return Ok(None);
+    };
+    if serde_json::from_slice::<Map<String, Value>>(&bytes).is_ok() {
+        return Ok(None);
+    }
+    let backup = path.with_extension("json.bak");
+    std::fs::rename(path, &backup).map_err(|e| format!("Failed to back up corrupt settings: {}", e))?;
+    Ok(Some(backup))
+}
+
+/// Fills in the default for every setting that isn't in `store` yet.
+pub fn apply_default_settings<R: tauri::Runtime>(store: &Store<R>) {
+    for (key, value) in missing_defaults(|key| store.has(key)) {
+        log::debug!("📝 Setting default {}: {}", key, value);
+        store.set(key, value);
+    }
+}
+
+/// The default of every setting that `is_set` says isn't stored yet and has one.
+fn missing_defaults(is_set: impl Fn(&str) -> bool) -> Vec<(&'static str, Value)> {
+    RULES
+        .iter()
+        .filter(|(key, _, _)| !is_set(key))
+        .filter_map(|(key, _, initial)| Some((*key, initial.value()?)))
+        .collect()
+}
+
+/// Every stored setting except the app's own runtime state.
+pub fn user_settings<R: tauri::Runtime>(store: &Store<R>) -> Map<String, Value> {
+    store
+        .entries()
+        .into_iter()
+        .filter(|(key, _)| !INTERNAL_KEYS.contains(&key.as_str()))
+        .collect()
+}
+
+#[tauri::command]
+pub fn export_settings(app: AppHandle) -> Result<String, String> {
+    log::debug!("📤 export_settings command received!");
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    serde_json::to_string_pretty(&user_settings(&store)).map_err(|e| e.to_string())
+}
+
+/// Replaces the settings named in `json` after validating all of them, then re-applies
+/// hotkeys and restarts the timer. Nothing is written if any value is rejected.
+#[tauri::command]
+pub fn import_settings(app: AppHandle, json: String) -> Result<(), String> {
+    log::debug!("📥 import_settings command received!");
+    let settings: Map<String, Value> =
+        serde_json::from_str(&json).map_err(|e| format!("Not a valid settings file: {}", e))?;
+
+    let problems: Vec<String> = settings
+        .iter()
+        .filter_map(|(key, value)| validate_value(key, value).err())
+        .collect();
+    if !problems.is_empty() {
+        return Err(problems.join("; "));
+    }
+
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    for (key, value) in settings {
+        store.set(key, value);
+    }
+    store.save().map_err(|e| e.to_string())?;
+    log::info!("✅ Settings imported");
+
+    crate::register_hotkeys_from_store(&app);
+    crate::restart_timer(app).map_err(|problems| problems.join("; "))
+}
+
+/// Restores every setting to its default, keeping only runtime state such as the paused
+/// timer. Hotkeys, the timer and the OS autostart entry are brought in line afterwards.
+#[tauri::command]
+pub fn reset_settings(app: AppHandle) -> Result<(), String> {
+    log::debug!("♻️ reset_settings command received!");
+    let store = app.store(SETTINGS_STORE).map_err(|e| e.to_string())?;
+    for key in store.keys() {
+        if !INTERNAL_KEYS.contains(&key.as_str()) {
+            store.delete(key);
+        }
+    }
+    apply_default_settings(&store);
+    store.save().map_err(|e| e.to_string())?;
+    log::info!("✅ Settings reset to defaults");
+
+    crate::register_hotkeys_from_store(&app);
+    crate::profiles::refresh_profiles_menu(&app);
+    crate::restart_timer(app.clone()).map_err(|problems| problems.join("; "))?;
+
+    let auto_start = store.get("autoStart").and_then(|v| v.as_bool()).unwrap_or(false);
+    crate::apply_autostart(&app, auto_start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh, empty directory of its own for one test.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("nudge-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn corrupt_settings_are_backed_up_and_replaced_by_defaults() {
+        let dir = temp_dir("corrupt-settings");
+        let path = dir.join(SETTINGS_STORE);
+        std::fs::write(&path, "{\"intervalMinutes\": 2").unwrap();
+
+        let backup = back_up_if_corrupt(&path).unwrap();
+
+        assert_eq!(backup, Some(dir.join("settings.json.bak")));
+        assert_eq!(std::fs::read_to_string(dir.join("settings.json.bak")).unwrap(), "{\"intervalMinutes\": 2");
+        assert!(!path.exists());
+        // The store then starts empty, so every default is filled in.
+        let defaults: Map<String, Value> = missing_defaults(|_| false)
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect();
+        assert_eq!(defaults["intervalMinutes"], json!(20));
+        assert_eq!(defaults["overlayDurationSeconds"], json!(30));
+        assert!(!defaults.contains_key("microBreakIntervalMinutes"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn readable_or_missing_settings_are_left_alone() {
+        let dir = temp_dir("readable-settings");
+        let path = dir.join(SETTINGS_STORE);
+        assert_eq!(back_up_if_corrupt(&path).unwrap(), None);
+
+        std::fs::write(&path, "{\"intervalMinutes\": 25}").unwrap();
+        assert_eq!(back_up_if_corrupt(&path).unwrap(), None);
+        assert!(path.exists());
+        assert!(!dir.join("settings.json.bak").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn defaults_pass_their_own_rules() {
+        for (key, value) in missing_defaults(|_| false) {
+            assert_eq!(validate_value(key, &value), Ok(()));
+        }
+    }
+
+    #[test]
+    fn defaults_leave_stored_settings_alone() {
+        let defaults = missing_defaults(|key| key == "intervalMinutes");
+        assert!(defaults.iter().all(|(key, _)| *key != "intervalMinutes"));
+        assert!(defaults.iter().any(|(key, _)| *key == "overlayDurationSeconds"));
+    }
+}
@@ -0,0 +1,299 @@
+//! Applies settings changes as they happen, so nothing has to call `restart_timer` by hand.
+//!
+//! Changes made through the store (from the settings window or the backend) arrive as
+//! `store://change` events. Edits made to `settings.json` behind the app's back are found
+//! by polling the file's modification time and comparing its contents with the store; the
+//! app's own saves leave the two equal and are passed over. Edited values are validated
+//! like any other and an invalid one is rejected, putting the previous value back in the
+//! file. Either way, changed keys are collected until writes have been quiet for
+//! `DEBOUNCE`, then applied together. The timer is only restarted if a setting it schedules
+//! by actually changed value.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Listener, Manager};
+use tauri_plugin_store::StoreExt;
+use tokio::sync::mpsc;
+
+use crate::{settings, SETTINGS_STORE};
+
+const DEBOUNCE: Duration = Duration::from_millis(500);
+const FILE_POLL_PERIOD: Duration = Duration::from_secs(2);
+
+/// Settings the running break loop is built from; a change to any of them restarts it.
+const TIMER_KEYS: &[&str] = &[
+    "intervalMinutes",
+    "microBreakIntervalMinutes",
+    "frequencyRamp",
+    "timers",
+    "longBreakEveryNMicroBreaks",
+    "pomodoroMode",
+    "pomodoroWorkMinutes",
+    "pomodoroBreakMinutes",
+    "pomodoroLongBreakMinutes",
+    "pomodoroLongBreakEvery",
+    "batterySaverProfile",
+    "batteryIntervalMultiplier",
+    "escalateAfterSkips",
+    "escalationIntervalPercent",
+    "workStartTime",
+    "workEndTime",
+    "workDays",
+    "weekdayOverrides",
+    "dndSchedules",
+    "maxBreaksPerDay",
+];
+const HOTKEY_KEYS: &[&str] = &["triggerHotkey", "dismissHotkey", "emergencyHotkey", "settingsHotkey"];
+const PROFILE_KEYS: &[&str] = &["profiles", "activeProfile"];
+
+/// The `TIMER_KEYS` values the running timer was last started with.
+pub struct AppliedSettings(Mutex<Vec<Option<Value>>>);
+
+/// What the file watcher knows about `settings.json`, shared with the `store://change`
+/// listener.
+#[derive(Default)]
+struct FileState {
+    /// Modification time of the version of the file last looked at.
+    last_modified: Option<SystemTime>,
+    /// Keys the app changed that may not have reached the file yet. The file disagreeing
+    /// with the store about these is the app's doing, not an outside edit.
+    unsaved: HashSet<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct StoreChange {
+    path: PathBuf,
+    key: String,
+}
+
+fn timer_settings(app: &AppHandle) -> Vec<Option<Value>> {
+    let store = app.store(SETTINGS_STORE).ok();
+    TIMER_KEYS
+        .iter()
+        .map(|key| store.as_ref().and_then(|store| store.get(key)))
+        .collect()
+}
+
+/// Records the current timer settings as applied. `restart_timer` calls this, so a change
+/// it has already acted on doesn't restart the timer a second time.
+pub fn mark_applied(app: &AppHandle) {
+    let current = timer_settings(app);
+    if let Some(applied) = app.try_state::<AppliedSettings>() {
+        *applied.0.lock().unwrap() = current;
+    }
+}
+
+pub fn start(app: &AppHandle) {
+    app.manage(AppliedSettings(Mutex::new(timer_settings(app))));
+    let path = match tauri_plugin_store::resolve_store_path(app, SETTINGS_STORE) {
+        Ok(path) => path,
+        Err(e) => {
+            log::error!("❌ Not watching settings, can't locate the store: {}", e);
+            return;
+        }
+    };
+
+    let file = Arc::new(Mutex::new(FileState {
+        last_modified: modified_at(&path),
+        unsaved: HashSet::new(),
+    }));
+    let (changes, mut changed_keys) = mpsc::unbounded_channel::<String>();
+    app.listen_any("store://change", {
+        let path = path.clone();
+        let file = file.clone();
+        move |event| {
+            let Ok(change) = serde_json::from_str::<StoreChange>(event.payload()) else {
+                return;
+            };
+            if change.path == path {
+                file.lock().unwrap().unsaved.insert(change.key.clone());
+                let _ = changes.send(change.key);
+            }
+        }
+    });
+    tauri::async_runtime::spawn(watch_file(app.clone(), path, file));
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(key) = changed_keys.recv().await {
+            let mut keys = HashSet::from([key]);
+            loop {
+                match tokio::time::timeout(DEBOUNCE, changed_keys.recv()).await {
+                    Ok(Some(key)) => {
+                        keys.insert(key);
+                    }
+                    Ok(None) => return,
+                    Err(_) => break,
+                }
+            }
+            apply_changes(&app, &keys);
+        }
+    });
+}
+
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn read_file(path: &Path) -> Result<Map<String, Value>, String> {
+    let contents = std::fs::read(path).map_err(|e| e.to_string())?;
+    serde_json::from_slice(&contents).map_err(|e| e.to_string())
+}
+
+/// Keys whose value in the file (`None` when missing) differs from the store's, leaving out
+/// the `unsaved` ones the app itself is still writing.
+fn edited_keys(
+    on_disk: &Map<String, Value>,
+    stored: &Map<String, Value>,
+    unsaved: &HashSet<String>,
+) -> Vec<(String, Option<Value>)> {
+    on_disk
+        .keys()
+        .chain(stored.keys().filter(|key| !on_disk.contains_key(*key)))
+        .filter(|key| !unsaved.contains(*key) && on_disk.get(*key) != stored.get(*key))
+        .map(|key| (key.clone(), on_disk.get(key).cloned()))
+        .collect()
+}
+
+/// Watches `settings.json` for edits made outside the app and puts them into the store,
+/// except for values that fail validation: those are rejected and the file is saved back
+/// with what the store had.
+async fn watch_file(app: AppHandle, path: PathBuf, file: Arc<Mutex<FileState>>) {
+    loop {
+        tokio::time::sleep(FILE_POLL_PERIOD).await;
+        let modified = modified_at(&path);
+        if modified == file.lock().unwrap().last_modified {
+            continue;
+        }
+        let Ok(store) = app.store(SETTINGS_STORE) else {
+            continue;
+        };
+        let on_disk = match read_file(&path) {
+            Ok(on_disk) => on_disk,
+            Err(e) => {
+                log::error!("❌ Failed to read settings.json: {}", e);
+                file.lock().unwrap().last_modified = modified;
+                continue;
+            }
+        };
+        let stored: Map<String, Value> = store.entries().into_iter().collect();
+        let edited = {
+            let mut file = file.lock().unwrap();
+            file.last_modified = modified;
+            // Whatever the app wrote that the file now agrees with has been saved.
+            file.unsaved.retain(|key| on_disk.get(key) != stored.get(key));
+            edited_keys(&on_disk, &stored, &file.unsaved)
+        };
+        if edited.is_empty() {
+            continue;
+        }
+
+        log::info!("📝 settings.json was edited outside the app");
+        let mut rejected = false;
+        for (key, value) in &edited {
+            match value {
+                Some(value) => match settings::validate_known_value(key, value) {
+                    Ok(()) => store.set(key.clone(), value.clone()),
+                    Err(e) => {
+                        log::warn!("⚠️ Rejecting edited setting, keeping the previous value: {}", e);
+                        rejected = true;
+                    }
+                },
+                None => {
+                    store.delete(key);
+                }
+            }
+        }
+        if rejected {
+            if let Err(e) = store.save() {
+                log::error!("❌ Failed to restore settings.json: {}", e);
+            }
+        }
+        let mut file = file.lock().unwrap();
+        // Applying the edits marked them unsaved, but they came from the file in the first place.
+        for (key, _) in &edited {
+            file.unsaved.remove(key);
+        }
+        if rejected {
+            file.last_modified = modified_at(&path);
+        }
+    }
+}
+
+fn apply_changes(app: &AppHandle, keys: &HashSet<String>) {
+    log::info!("🔄 Settings changed: {:?}", keys);
+    if let Ok(store) = app.store(SETTINGS_STORE) {
+        for key in keys {
+            if let Some(value) = store.get(key) {
+                if let Err(e) = settings::validate_known_value(key, &value) {
+                    log::warn!("⚠️ Changed setting is invalid and will be ignored: {}", e);
+                }
+            }
+        }
+    }
+
+    let changed = |group: &[&str]| keys.iter().any(|key| group.contains(&key.as_str()));
+    if changed(HOTKEY_KEYS) {
+        crate::register_hotkeys_from_store(app);
+    }
+    if changed(PROFILE_KEYS) {
+        crate::profiles::refresh_profiles_menu(app);
+    }
+
+    let timer_changed = {
+        let Some(applied) = app.try_state::<AppliedSettings>() else {
+            return;
+        };
+        let applied = applied.0.lock().unwrap();
+        *applied != timer_settings(app)
+    };
+    if timer_changed {
+        if let Err(problems) = crate::restart_timer(app.clone()) {
+            log::warn!("⚠️ Timer settings changed but can't be applied: {}", problems.join("; "));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn map(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn a_file_matching_the_store_has_no_edits() {
+        let settings = map(json!({ "intervalMinutes": 20, "strictMode": false }));
+        assert!(edited_keys(&settings, &settings, &HashSet::new()).is_empty());
+    }
+
+    #[test]
+    fn outside_edits_and_removals_are_found() {
+        let stored = map(json!({ "intervalMinutes": 20, "strictMode": false }));
+        let on_disk = map(json!({ "intervalMinutes": 45, "language": "de" }));
+        let mut edited = edited_keys(&on_disk, &stored, &HashSet::new());
+        edited.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            edited,
+            vec![
+                ("intervalMinutes".to_string(), Some(json!(45))),
+                ("language".to_string(), Some(json!("de"))),
+                ("strictMode".to_string(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn keys_the_app_has_yet_to_save_are_not_edits() {
+        let stored = map(json!({ "intervalMinutes": 30 }));
+        let on_disk = map(json!({ "intervalMinutes": 20 }));
+        let unsaved = HashSet::from(["intervalMinutes".to_string()]);
+        assert!(edited_keys(&on_disk, &stored, &unsaved).is_empty());
+    }
+}
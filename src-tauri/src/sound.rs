@@ -0,0 +1,115 @@
+//! Audio cues for the start and end of a break.
+//!
+//! A sound setting is either empty (silent), the name of a built-in tone, or a path to an
+//! audio file. Files are decoded up front so unreadable ones are reported before playback;
+//! playback itself runs on its own thread because the output stream can't leave it.
+
+use std::fs::File;
+use std::io::BufReader;
+use std::time::Duration;
+
+use rodio::source::SineWave;
+use rodio::{Decoder, OutputStream, Sink, Source};
+use tauri::AppHandle;
+
+type BoxedSource = Box<dyn Source<Item = f32> + Send>;
+
+#[derive(Clone, Copy, Debug)]
+pub enum SoundCue {
+    BreakStart,
+    BreakEnd,
+}
+
+impl SoundCue {
+    fn setting_key(self) -> &'static str {
+        match self {
+            SoundCue::BreakStart => "breakStartSound",
+            SoundCue::BreakEnd => "breakEndSound",
+        }
+    }
+}
+
+fn tone(frequency: f32, millis: u64) -> BoxedSource {
+    Box::new(
+        SineWave::new(frequency)
+            .take_duration(Duration::from_millis(millis))
+            .amplify(0.25),
+    )
+}
+
+/// Sources for a built-in sound name, or `None` if `name` isn't one.
+fn built_in(name: &str) -> Option<Vec<BoxedSource>> {
+    match name {
+        "chime" => Some(vec![tone(660.0, 150), tone(880.0, 250)]),
+        "bell" => Some(vec![tone(523.25, 600)]),
+        "soft" => Some(vec![tone(440.0, 300)]),
+        _ => None,
+    }
+}
+
+fn load(sound: &str) -> Result<Vec<BoxedSource>, String> {
+    if let Some(sources) = built_in(sound) {
+        return Ok(sources);
+    }
+    let file = File::open(sound).map_err(|e| format!("Can't open sound '{}': {}", sound, e))?;
+    let decoder = Decoder::new(BufReader::new(file))
+        .map_err(|e| format!("Can't decode sound '{}': {}", sound, e))?;
+    Ok(vec![Box::new(decoder.convert_samples())])
+}
+
+/// Plays `sound` at `volume` (0–100) without blocking the caller.
+pub fn play(sound: &str, volume: u64) -> Result<(), String> {
+    let sources = load(sound)?;
+    let volume = volume.min(100) as f32 / 100.0;
+
+    std::thread::spawn(move || {
+        let (_stream, handle) = match OutputStream::try_default() {
+            Ok(output) => output,
+            Err(e) => {
+                log::error!("❌ No audio output available: {}", e);
+                return;
+            }
+        };
+        let sink = match Sink::try_new(&handle) {
+            Ok(sink) => sink,
+            Err(e) => {
+                log::error!("❌ Failed to open audio sink: {}", e);
+                return;
+            }
+        };
+        sink.set_volume(volume);
+        for source in sources {
+            sink.append(source);
+        }
+        sink.sleep_until_end();
+    });
+    Ok(())
+}
+
+/// Plays the sound configured for `cue`, if any. Problems are logged and otherwise ignored.
+pub fn play_cue(app: &AppHandle, cue: SoundCue) {
+    let sound = crate::get_string_setting(app, cue.setting_key(), "");
+    if sound.is_empty() {
+        return;
+    }
+    log::info!("🔊 Playing {:?} sound: {}", cue, sound);
+    if let Err(e) = play(&sound, crate::get_u64_setting(app, "volume", 70)) {
+        log::error!("❌ Skipping {:?} sound: {}", cue, e);
+    }
+}
+
+/// Plays the start (`which = "start"`) or end (`"end"`) sound so settings can preview it.
+#[tauri::command]
+pub fn test_sound(app: AppHandle, which: String) -> Result<(), String> {
+    log::debug!("🔊 test_sound command received: {}", which);
+    let cue = match which.as_str() {
+        "start" => SoundCue::BreakStart,
+        "end" => SoundCue::BreakEnd,
+        other => return Err(format!("Unknown sound '{}', expected 'start' or 'end'", other)),
+    };
+    let sound = crate::get_string_setting(&app, cue.setting_key(), "");
+    if sound.is_empty() {
+        return Err("No sound is configured".to_string());
+    }
+    play(&sound, crate::get_u64_setting(&app, "volume", 70))
+}
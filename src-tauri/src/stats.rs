@@ -0,0 +1,316 @@
+//! Break statistics persisted in `stats.json`.
+//!
+//! Daily and weekly counters carry the date they belong to, so a rollover is detected by
+//! comparing date strings rather than relying on how long the process has been running.
+//!
+//! Every read-modify-write of the file, and the quota's read of today's count, goes through
+//! `StatsState`, so a break recorded from the timer and one recorded by the lifecycle
+//! subscriber can't overwrite each other's counts.
+//!
+//! The streak counts consecutive days with at least one completed break. It is checked at
+//! launch and at every local midnight, and drops to zero once a whole day passes without one.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use chrono::{Datelike, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_store::StoreExt;
+
+use crate::events::LifecycleEvent;
+
+const STATS_STORE: &str = "stats.json";
+/// Streak lengths, in days, that emit `streak-milestone`. Past the last one, every further
+/// year does.
+const STREAK_MILESTONES: &[u64] = &[3, 7, 14, 30, 60, 100, 180, 365];
+
+/// Serializes access to `stats.json`, held for the whole of each read-modify-write.
+#[derive(Default)]
+pub struct StatsState(Mutex<()>);
+
+fn lock(app: &AppHandle) -> MutexGuard<'_, ()> {
+    app.state::<StatsState>().inner().0.lock().unwrap()
+}
+
+/// How a break ended, or why it never showed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakOutcome {
+    Completed,
+    /// Skipped or dismissed before the countdown ran out.
+    UserSkipped,
+    /// Held back by the app itself, e.g. for a fullscreen app or a do-not-disturb window.
+    AutoPostponed,
+    /// Snoozed or postponed by the user.
+    Snoozed,
+    /// Fell due while the computer was asleep and was dropped (`skipMissedBreaks`).
+    Missed,
+}
+
+impl BreakOutcome {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BreakOutcome::Completed => "completed",
+            BreakOutcome::UserSkipped => "skipped",
+            BreakOutcome::AutoPostponed => "auto-postponed",
+            BreakOutcome::Snoozed => "snoozed",
+            BreakOutcome::Missed => "missed",
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakCounts {
+    pub completed: u64,
+    /// Breaks the user skipped or dismissed.
+    pub skipped: u64,
+    // Counted since after the first stats were written, so missing from older files.
+    #[serde(default)]
+    pub auto_postponed: u64,
+    #[serde(default)]
+    pub snoozed: u64,
+    #[serde(default)]
+    pub missed: u64,
+}
+
+impl BreakCounts {
+    fn add(&mut self, outcome: BreakOutcome) {
+        match outcome {
+            BreakOutcome::Completed => self.completed += 1,
+            BreakOutcome::UserSkipped => self.skipped += 1,
+            BreakOutcome::AutoPostponed => self.auto_postponed += 1,
+            BreakOutcome::Snoozed => self.snoozed += 1,
+            BreakOutcome::Missed => self.missed += 1,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Stats {
+    pub today: BreakCounts,
+    pub this_week: BreakCounts,
+    pub all_time: BreakCounts,
+    /// All-time counts split by break kind.
+    pub by_kind: HashMap<String, BreakCounts>,
+    pub total_break_minutes: u64,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Streak {
+    pub current: u64,
+    pub longest: u64,
+    /// Last day with a completed break, as `YYYY-MM-DD`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_day: Option<String>,
+}
+
+impl Streak {
+    /// Drops the current streak if a whole day has passed since the last completed break.
+    fn expire(&mut self, today: NaiveDate) {
+        let last_day = self.last_day.as_deref().and_then(|day| day.parse::<NaiveDate>().ok());
+        let alive = last_day.is_some_and(|day| day >= today - chrono::Days::new(1));
+        if !alive {
+            self.current = 0;
+        }
+    }
+
+    /// Counts a completed break today. Returns true if that made the streak a day longer.
+    fn extend(&mut self, today: NaiveDate) -> bool {
+        let today_key = today.to_string();
+        if self.last_day.as_deref() == Some(today_key.as_str()) {
+            return false;
+        }
+        self.expire(today);
+        self.current += 1;
+        self.longest = self.longest.max(self.current);
+        self.last_day = Some(today_key);
+        true
+    }
+}
+
+fn is_streak_milestone(days: u64) -> bool {
+    let last = STREAK_MILESTONES[STREAK_MILESTONES.len() - 1];
+    STREAK_MILESTONES.contains(&days) || (days > last && days.is_multiple_of(365))
+}
+
+fn week_start(date: NaiveDate) -> NaiveDate {
+    date - chrono::Days::new(u64::from(date.weekday().num_days_from_monday()))
+}
+
+/// Loads counts stored under `key`, discarding them if they were recorded for another period.
+fn load_period<R: tauri::Runtime>(
+    store: &tauri_plugin_store::Store<R>,
+    key: &str,
+    date_key: &str,
+    period: &str,
+) -> BreakCounts {
+    let same_period = store
+        .get(date_key)
+        .is_some_and(|v| v.as_str() == Some(period));
+    if !same_period {
+        return BreakCounts::default();
+    }
+    store
+        .get(key)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+fn load<T: for<'de> Deserialize<'de> + Default, R: tauri::Runtime>(
+    store: &tauri_plugin_store::Store<R>,
+    key: &str,
+) -> T {
+    store
+        .get(key)
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Records the breaks that end, as published on the lifecycle bus. The extra timers' breaks
+/// are counted under their own names.
+pub fn start_recording(app: &AppHandle) {
+    crate::events::subscribe(app, "stats", |app, event| async move {
+        let (kind, timer, elapsed, outcome) = match event {
+            LifecycleEvent::BreakEnded { kind, timer, elapsed } => (kind, timer, elapsed, BreakOutcome::Completed),
+            LifecycleEvent::Skipped { kind, timer, elapsed } => (kind, timer, elapsed, BreakOutcome::UserSkipped),
+            _ => return,
+        };
+        let stats_kind = timer.as_deref().unwrap_or(kind.as_str());
+        if let Err(e) = record_break(&app, stats_kind, outcome, elapsed) {
+            log::error!("❌ Failed to record break: {}", e);
+        }
+    });
+}
+
+/// Records how a break ended, or that it was put off. `duration` is the time spent on it.
+/// The event also goes into the break history.
+pub fn record_break(app: &AppHandle, kind: &str, outcome: BreakOutcome, duration: Duration) -> Result<(), String> {
+    let _guard = lock(app);
+    if let Err(e) = crate::history::append(app, kind, outcome, duration) {
+        log::error!("❌ Failed to add the break to the history: {}", e);
+    }
+    let store = app.store(STATS_STORE).map_err(|e| e.to_string())?;
+    let today = Local::now().date_naive();
+    let today_key = today.to_string();
+    let week_key = week_start(today).to_string();
+
+    let mut today_counts = load_period(&store, "today", "todayDate", &today_key);
+    let mut week_counts = load_period(&store, "thisWeek", "weekStart", &week_key);
+    let mut all_time: BreakCounts = load(&store, "allTime");
+    let mut by_kind: HashMap<String, BreakCounts> = load(&store, "byKind");
+    let total_seconds: u64 = load(&store, "totalBreakSeconds");
+
+    today_counts.add(outcome);
+    week_counts.add(outcome);
+    all_time.add(outcome);
+    by_kind.entry(kind.to_string()).or_default().add(outcome);
+
+    store.set("todayDate", json!(today_key));
+    store.set("today", json!(today_counts));
+    store.set("weekStart", json!(week_key));
+    store.set("thisWeek", json!(week_counts));
+    store.set("allTime", json!(all_time));
+    store.set("byKind", json!(by_kind));
+    store.set("totalBreakSeconds", json!(total_seconds + duration.as_secs()));
+
+    let mut milestone = None;
+    if outcome == BreakOutcome::Completed {
+        let mut streak: Streak = load(&store, "streak");
+        if streak.extend(today) {
+            log::info!("🔥 Break streak now {} day(s)", streak.current);
+            milestone = is_streak_milestone(streak.current).then_some(streak.current);
+        }
+        store.set("streak", json!(streak));
+    }
+    store.save().map_err(|e| e.to_string())?;
+    if let Some(days) = milestone {
+        log::info!("🏆 Break streak milestone: {} days", days);
+        if let Err(e) = app.emit("streak-milestone", days) {
+            log::error!("❌ Failed to emit streak-milestone: {}", e);
+        }
+    }
+
+    log::info!(
+        "📊 Recorded {} break ({}, {}s)",
+        kind,
+        outcome.as_str(),
+        duration.as_secs()
+    );
+    Ok(())
+}
+
+/// Breaks completed today (local time). Reads 0 as soon as the date rolls over.
+pub fn completed_today(app: &AppHandle) -> u64 {
+    let _guard = lock(app);
+    let Ok(store) = app.store(STATS_STORE) else {
+        return 0;
+    };
+    let today = Local::now().date_naive().to_string();
+    load_period(&store, "today", "todayDate", &today).completed
+}
+
+#[tauri::command]
+pub fn get_stats(app: AppHandle) -> Result<Stats, String> {
+    log::debug!("📊 get_stats command received!");
+    let _guard = lock(&app);
+    let store = app.store(STATS_STORE).map_err(|e| e.to_string())?;
+    let today = Local::now().date_naive();
+    let total_seconds: u64 = load(&store, "totalBreakSeconds");
+
+    Ok(Stats {
+        today: load_period(&store, "today", "todayDate", &today.to_string()),
+        this_week: load_period(&store, "thisWeek", "weekStart", &week_start(today).to_string()),
+        all_time: load(&store, "allTime"),
+        by_kind: load(&store, "byKind"),
+        total_break_minutes: total_seconds / 60,
+    })
+}
+
+/// Ends the streak if yesterday had no completed break, saving the result.
+fn refresh_streak(app: &AppHandle) -> Result<Streak, String> {
+    let _guard = lock(app);
+    let store = app.store(STATS_STORE).map_err(|e| e.to_string())?;
+    let mut streak: Streak = load(&store, "streak");
+    let before = streak.current;
+    streak.expire(Local::now().date_naive());
+    if streak.current != before {
+        log::info!("🧊 Break streak of {} day(s) ended", before);
+        store.set("streak", json!(streak));
+        store.save().map_err(|e| e.to_string())?;
+    }
+    Ok(streak)
+}
+
+/// Checks the streak now and again at every local midnight.
+pub fn start_streak_tracking(app: &AppHandle) {
+    if let Err(e) = refresh_streak(app) {
+        log::error!("❌ Failed to check the break streak: {}", e);
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let now = Local::now().naive_local();
+            let midnight = (now.date() + chrono::Days::new(1)).and_time(chrono::NaiveTime::MIN);
+            // A second past midnight, so the date has certainly rolled over.
+            let wait = (midnight - now).to_std().unwrap_or_default() + Duration::from_secs(1);
+            tokio::time::sleep(wait).await;
+            if let Err(e) = refresh_streak(&app) {
+                log::error!("❌ Failed to check the break streak: {}", e);
+            }
+        }
+    });
+}
+
+/// Days in a row with at least one completed break, and the longest such run.
+#[tauri::command]
+pub fn get_streak(app: AppHandle) -> Result<Streak, String> {
+    log::debug!("🔥 get_streak command received!");
+    let mut streak = refresh_streak(&app)?;
+    streak.last_day = None;
+    Ok(streak)
+}
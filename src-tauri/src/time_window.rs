@@ -0,0 +1,103 @@
+//! Recurring daily time windows such as `{ "start": "22:00", "end": "07:00", "days": ["fri"] }`.
+
+use chrono::{Datelike, Days, NaiveDateTime, NaiveTime, Weekday};
+use serde::Deserialize;
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::SETTINGS_STORE;
+
+/// A window as stored in settings. Days are weekday names ("mon", "Tuesday", ...);
+/// an empty list means every day.
+#[derive(Deserialize)]
+pub struct TimeWindowSetting {
+    pub start: String,
+    pub end: String,
+    #[serde(default)]
+    pub days: Vec<String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TimeWindow {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    /// Days the window starts on. Empty means every day.
+    pub days: Vec<Weekday>,
+}
+
+pub fn parse_hhmm(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value.trim(), "%H:%M")
+        .map_err(|_| format!("'{}' is not a valid HH:MM time", value))
+}
+
+pub fn parse_weekday(value: &str) -> Result<Weekday, String> {
+    value
+        .trim()
+        .parse::<Weekday>()
+        .map_err(|_| format!("'{}' is not a valid weekday", value))
+}
+
+impl TimeWindow {
+    pub fn from_setting(setting: &TimeWindowSetting) -> Result<Self, String> {
+        Ok(TimeWindow {
+            start: parse_hhmm(&setting.start)?,
+            end: parse_hhmm(&setting.end)?,
+            days: setting
+                .days
+                .iter()
+                .map(|day| parse_weekday(day))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn applies_on(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    /// Whether `at` falls inside the window. A window whose end is not after its start runs
+    /// past midnight; its `days` refer to the day it starts on.
+    pub fn contains(&self, at: NaiveDateTime) -> bool {
+        let time = at.time();
+        let day = at.weekday();
+        if self.start < self.end {
+            self.applies_on(day) && time >= self.start && time < self.end
+        } else {
+            (self.applies_on(day) && time >= self.start) || (self.applies_on(day.pred()) && time < self.end)
+        }
+    }
+}
+
+impl TimeWindow {
+    /// The first time after `after` at which the window opens, looking up to a week ahead.
+    pub fn next_start(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        (0..=7)
+            .filter_map(|offset| after.date().checked_add_days(Days::new(offset)))
+            .filter(|date| self.applies_on(date.weekday()))
+            .map(|date| date.and_time(self.start))
+            .find(|start| *start > after)
+    }
+}
+
+/// Loads the list of windows stored under `key`. Malformed entries are logged and skipped.
+pub fn load_windows(app: &AppHandle, key: &str) -> Vec<TimeWindow> {
+    let Some(value) = app.store(SETTINGS_STORE).ok().and_then(|store| store.get(key)) else {
+        return Vec::new();
+    };
+    let settings: Vec<TimeWindowSetting> = match serde_json::from_value(value) {
+        Ok(settings) => settings,
+        Err(e) => {
+            log::error!("❌ Ignoring malformed {}: {}", key, e);
+            return Vec::new();
+        }
+    };
+    settings
+        .iter()
+        .filter_map(|setting| match TimeWindow::from_setting(setting) {
+            Ok(window) => Some(window),
+            Err(e) => {
+                log::error!("❌ Ignoring {} entry: {}", key, e);
+                None
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,119 @@
+//! Extra reminder timers that run alongside the regular break schedule, each on its own
+//! interval with its own overlay text: one for posture and one for water, say.
+//!
+//! Every entry of `timers` gets a loop of its own, keyed by name in `TimerState` and run by
+//! the same `run_timer_loop` as the regular schedule, so idle time, work hours,
+//! do-not-disturb, the daily quota and fullscreen apps hold them back alike. When one comes
+//! due while any break is on screen it waits its turn, and the regular schedule likewise
+//! waits for it, so overlays never stack. Their breaks can be skipped or dismissed like any
+//! other, but not snoozed, and ending one early leaves the regular schedule where it was.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::time::Instant;
+
+use crate::reminders::ReminderType;
+use crate::{TimerId, TimerState};
+
+/// How often a due timer checks whether the break in its way has ended.
+const QUEUE_POLL_PERIOD: Duration = Duration::from_secs(1);
+
+/// One entry of `timers`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimerConfig {
+    pub name: String,
+    pub interval_minutes: u64,
+    pub duration_seconds: u64,
+    #[serde(default)]
+    pub icon: String,
+    pub message: String,
+}
+
+impl TimerConfig {
+    /// What the overlay shows for this timer's breaks.
+    pub fn reminder(&self) -> ReminderType {
+        ReminderType {
+            name: self.name.clone(),
+            icon: self.icon.clone(),
+            message: self.message.clone(),
+            weight: 1,
+        }
+    }
+}
+
+pub fn validate_timers(timers: &[TimerConfig]) -> Result<(), String> {
+    for (index, timer) in timers.iter().enumerate() {
+        if timer.name.trim().is_empty() || timer.message.trim().is_empty() {
+            return Err("each timer needs a name and a message".to_string());
+        }
+        if timers[..index].iter().any(|other| other.name == timer.name) {
+            return Err(format!("there is more than one timer named '{}'", timer.name));
+        }
+        if !(1..=480).contains(&timer.interval_minutes) {
+            return Err(format!("timer '{}' needs an interval between 1 and 480 minutes", timer.name));
+        }
+        if !(5..=600).contains(&timer.duration_seconds) {
+            return Err(format!("timer '{}' needs a duration between 5 and 600 seconds", timer.name));
+        }
+    }
+    Ok(())
+}
+
+pub fn load_timers(app: &AppHandle) -> Vec<TimerConfig> {
+    crate::get_setting(app, "timers")
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+/// Where one extra timer stands, for `get_timers_status`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedTimerStatus {
+    name: String,
+    interval_minutes: u64,
+    seconds_until_next_break: u64,
+}
+
+/// Waits until no break is on screen. Checked under the same lock `open_overlays` claims
+/// the screen with, so a break that slips in between just gets this one brought to the front.
+pub async fn wait_for_screen(app: &AppHandle) {
+    while app.state::<TimerState>().0.lock().unwrap().break_active {
+        tokio::time::sleep(QUEUE_POLL_PERIOD).await;
+    }
+}
+
+/// For the regular schedule: waits while one of these timers' breaks is on screen, so the
+/// regular break follows it instead of being dropped.
+pub async fn wait_for_timer_break(app: &AppHandle) {
+    loop {
+        {
+            let timer_state = app.state::<TimerState>();
+            let timer = timer_state.0.lock().unwrap();
+            if !timer.break_active || timer.break_timer.is_none() {
+                return;
+            }
+        }
+        tokio::time::sleep(QUEUE_POLL_PERIOD).await;
+    }
+}
+
+/// When each extra timer is next due. The regular schedule is in `get_timer_status`.
+#[tauri::command]
+pub fn get_timers_status(app: AppHandle) -> Vec<NamedTimerStatus> {
+    log::debug!("⏱️ get_timers_status command received!");
+    let timer_state = app.state::<TimerState>();
+    let timer = timer_state.0.lock().unwrap();
+    load_timers(&app)
+        .into_iter()
+        .map(|config| NamedTimerStatus {
+            seconds_until_next_break: timer
+                .deadline(&TimerId::Named(config.name.clone()))
+                .map_or(0, |deadline| deadline.saturating_duration_since(Instant::now()).as_secs()),
+            interval_minutes: config.interval_minutes,
+            name: config.name,
+        })
+        .collect()
+}
@@ -0,0 +1,92 @@
+//! Tray icon variants showing whether nudges are running, paused or on a break.
+//!
+//! `refresh_tray` picks the variant from the timer state and only swaps the icon when it
+//! changes. With `animatedTrayDuringBreak` on, the icon blinks between the break and running
+//! variants for as long as a break is on screen.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::image::Image;
+use tauri::{AppHandle, Manager};
+
+use crate::{TimerState, TrayState};
+
+const RUNNING_ICON: Image<'static> = tauri::include_image!("icons/tray/running.png");
+const PAUSED_ICON: Image<'static> = tauri::include_image!("icons/tray/paused.png");
+const BREAK_ICON: Image<'static> = tauri::include_image!("icons/tray/break.png");
+
+/// How long each frame of the break animation stays up.
+const ANIMATION_FRAME: Duration = Duration::from_millis(600);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrayIconKind {
+    Running,
+    /// Paused, in focus mode or snoozed until tomorrow: no break is coming.
+    Paused,
+    Break,
+}
+
+impl TrayIconKind {
+    fn image(self) -> Image<'static> {
+        match self {
+            TrayIconKind::Running => RUNNING_ICON,
+            TrayIconKind::Paused => PAUSED_ICON,
+            TrayIconKind::Break => BREAK_ICON,
+        }
+    }
+}
+
+/// The variant last put in the tray; `None` until the first refresh.
+#[derive(Default)]
+pub struct ShownTrayIcon(Mutex<Option<TrayIconKind>>);
+
+fn set_icon(app: &AppHandle, kind: TrayIconKind) {
+    let Some(tray) = app.try_state::<TrayState>() else {
+        return;
+    };
+    if let Err(e) = tray.icon.set_icon(Some(kind.image())) {
+        log::error!("❌ Failed to set tray icon: {}", e);
+    }
+}
+
+/// Puts `kind` in the tray unless it is already there.
+pub fn show(app: &AppHandle, kind: TrayIconKind) {
+    let shown = app.state::<ShownTrayIcon>();
+    let mut shown = shown.0.lock().unwrap();
+    if *shown == Some(kind) {
+        return;
+    }
+    log::debug!("🖼️ Tray icon: {:?}", kind);
+    *shown = Some(kind);
+    set_icon(app, kind);
+}
+
+/// Puts the right variant back in the tray even if it looks to be there already, for when
+/// the tray may have been rebuilt or left mid-animation, such as after a sleep.
+pub fn reset(app: &AppHandle) {
+    *app.state::<ShownTrayIcon>().0.lock().unwrap() = None;
+    crate::refresh_tray(app);
+}
+
+/// Blinks the tray icon until the current break ends, then hands back to `refresh_tray`.
+pub fn start_break_animation(app: &AppHandle) {
+    if !crate::get_bool_setting(app, "animatedTrayDuringBreak", false) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let frames = [TrayIconKind::Running, TrayIconKind::Break];
+        let mut frame = 0;
+        loop {
+            tokio::time::sleep(ANIMATION_FRAME).await;
+            if !app.state::<TimerState>().0.lock().unwrap().break_active {
+                break;
+            }
+            set_icon(&app, frames[frame % frames.len()]);
+            frame += 1;
+        }
+        // The animation left whatever frame was last up, so force the real one back.
+        reset(&app);
+    });
+}
@@ -0,0 +1,83 @@
+//! Optional HTTP callback: break events are POSTed as JSON to the `webhookUrl` setting.
+
+use std::time::Duration;
+
+use tauri::AppHandle;
+
+use crate::events::{self, LifecycleEvent};
+use crate::BreakKind;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(serde::Serialize)]
+struct WebhookPayload<'a> {
+    event: &'a str,
+    kind: BreakKind,
+    timestamp: String,
+    /// Only set for the events that end a break.
+    completed: Option<bool>,
+}
+
+fn webhook_url(app: &AppHandle) -> Option<String> {
+    let url = crate::get_string_setting(app, "webhookUrl", "");
+    let url = url.trim();
+    (!url.is_empty()).then(|| url.to_string())
+}
+
+async fn post(url: &str, payload: &WebhookPayload<'_>) -> Result<reqwest::StatusCode, String> {
+    let client = reqwest::Client::builder()
+        .timeout(WEBHOOK_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client.post(url).json(payload).send().await.map_err(|e| e.to_string())?;
+    Ok(response.status())
+}
+
+/// Sends `event` to the webhook in the background, if one is configured. Failures are only
+/// logged so a slow or broken endpoint never holds up the timer.
+fn notify(app: &AppHandle, event: &'static str, kind: BreakKind, completed: Option<bool>) {
+    let Some(url) = webhook_url(app) else {
+        return;
+    };
+    let payload = WebhookPayload {
+        event,
+        kind,
+        timestamp: chrono::Local::now().to_rfc3339(),
+        completed,
+    };
+    tauri::async_runtime::spawn(async move {
+        match post(&url, &payload).await {
+            Ok(status) if status.is_success() => log::debug!("🌐 Webhook '{}' delivered ({})", event, status),
+            Ok(status) => log::warn!("⚠️ Webhook '{}' answered {}", event, status),
+            Err(e) => log::error!("❌ Webhook '{}' failed: {}", event, e),
+        }
+    });
+}
+
+/// Posts break starts and ends from the lifecycle bus to the webhook.
+pub fn start(app: &AppHandle) {
+    events::subscribe(app, "webhook", |app, event| async move {
+        match event {
+            LifecycleEvent::BreakShown { kind, .. } => notify(&app, "break-started", kind, None),
+            LifecycleEvent::BreakEnded { kind, .. } => notify(&app, "break-finished", kind, Some(true)),
+            LifecycleEvent::Skipped { kind, .. } => notify(&app, "break-skipped", kind, Some(false)),
+            _ => {}
+        }
+    });
+}
+
+/// Sends a sample payload to the configured webhook and returns the HTTP status it answered with.
+#[tauri::command]
+pub async fn test_webhook(app: AppHandle) -> Result<String, String> {
+    log::debug!("🌐 test_webhook command received!");
+    let url = webhook_url(&app).ok_or_else(|| "No webhook URL is set".to_string())?;
+    let payload = WebhookPayload {
+        event: "test",
+        kind: BreakKind::Micro,
+        timestamp: chrono::Local::now().to_rfc3339(),
+        completed: None,
+    };
+    let status = post(&url, &payload).await?;
+    log::info!("🌐 Test webhook answered {}", status);
+    Ok(status.to_string())
+}
@@ -0,0 +1,200 @@
+//! Per-weekday schedules: `weekdayOverrides` maps a day name ("Fri", "Friday") to settings
+//! that replace the regular ones on that day, e.g. a longer interval on lighter Fridays.
+//!
+//! Only the settings in `OVERRIDABLE_KEYS` can be overridden, each within the same range as
+//! the setting itself. Their readers go through `WeekdaySettings`, which reads the store once
+//! and resolves every day's overrides, so the timer picks them up at the start of every
+//! cycle; at midnight the timer is restarted so a cycle running across the change of day
+//! doesn't keep yesterday's interval.
+
+use std::time::Duration;
+
+use chrono::Weekday;
+use serde_json::{Map, Value};
+use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
+
+use crate::schedule::Clock;
+use crate::{settings, time_window, SystemClock, SETTINGS_STORE};
+
+/// Settings a weekday may override.
+const OVERRIDABLE_KEYS: &[&str] = &[
+    "intervalMinutes",
+    "microBreakIntervalMinutes",
+    "overlayDurationSeconds",
+    "microBreakDurationSeconds",
+    "longBreakEveryNMicroBreaks",
+    "longBreakDurationSeconds",
+    "workStartTime",
+    "workEndTime",
+];
+/// How often the date is checked for the midnight rollover.
+const ROLLOVER_CHECK_PERIOD: Duration = Duration::from_secs(30);
+
+/// Checks `weekdayOverrides`: known day names, overridable keys, and values within range.
+pub fn validate_weekday_overrides(overrides: &Map<String, Value>) -> Result<(), String> {
+    overrides.iter().try_for_each(|(day, settings)| {
+        time_window::parse_weekday(day)?;
+        let settings = settings
+            .as_object()
+            .ok_or_else(|| format!("'{}' must be a map of settings", day))?;
+        settings.iter().try_for_each(|(key, value)| {
+            if !OVERRIDABLE_KEYS.contains(&key.as_str()) {
+                return Err(format!("'{}' can't be overridden per weekday", key));
+            }
+            settings::validate_value(key, value).map_err(|e| format!("{}: {}", day, e))
+        })
+    })
+}
+
+/// The settings as they stand on each weekday, read from the store in one go: a day's entry
+/// of `weekdayOverrides` in front of the regular settings. Invalid values count as missing,
+/// as with `get_setting`.
+pub struct WeekdaySettings {
+    base: Map<String, Value>,
+    overrides: Vec<(Weekday, Map<String, Value>)>,
+}
+
+impl WeekdaySettings {
+    pub fn load(app: &AppHandle) -> Self {
+        let base = app
+            .store(SETTINGS_STORE)
+            .map(|store| store.entries().into_iter().collect())
+            .unwrap_or_default();
+        Self::from_settings(base)
+    }
+
+    fn from_settings(base: Map<String, Value>) -> Self {
+        let overrides = base
+            .get("weekdayOverrides")
+            .and_then(Value::as_object)
+            .into_iter()
+            .flatten()
+            .filter_map(|(day, settings)| {
+                let day = time_window::parse_weekday(day).ok()?;
+                let settings = settings.as_object()?.iter().filter(|(key, _)| OVERRIDABLE_KEYS.contains(&key.as_str()));
+                Some((day, settings.map(|(key, value)| (key.clone(), value.clone())).collect()))
+            })
+            .collect();
+        WeekdaySettings { base, overrides }
+    }
+
+    fn override_on(&self, key: &str, day: Weekday) -> Option<&Value> {
+        self.overrides
+            .iter()
+            .filter(|(name, _)| *name == day)
+            .find_map(|(_, settings)| settings.get(key))
+            .filter(|value| valid(key, value))
+    }
+
+    fn regular(&self, key: &str) -> Option<&Value> {
+        self.base.get(key).filter(|value| valid(key, value))
+    }
+
+    /// `key` as it stands on `day`: that day's override if it has one, else the regular setting.
+    pub fn get(&self, key: &str, day: Weekday) -> Option<&Value> {
+        self.override_on(key, day).or_else(|| self.regular(key))
+    }
+
+    /// The first of `keys` overridden on `day`, else the first of them set at all. For a
+    /// setting listed with the older ones it replaces, so that a day overriding any of them
+    /// wins over the regular value of each.
+    pub fn first_of(&self, keys: &[&str], day: Weekday) -> Option<&Value> {
+        keys.iter()
+            .find_map(|key| self.override_on(key, day))
+            .or_else(|| keys.iter().find_map(|key| self.regular(key)))
+    }
+
+    pub fn u64_on(&self, key: &str, day: Weekday, default: u64) -> u64 {
+        self.get(key, day).and_then(Value::as_u64).unwrap_or(default)
+    }
+
+    pub fn string_on(&self, key: &str, day: Weekday) -> Option<&str> {
+        self.get(key, day).and_then(Value::as_str)
+    }
+}
+
+fn valid(key: &str, value: &Value) -> bool {
+    match settings::validate_known_value(key, value) {
+        Ok(()) => true,
+        Err(e) => {
+            log::warn!("⚠️ Ignoring invalid setting: {}", e);
+            false
+        }
+    }
+}
+
+fn has_overrides(app: &AppHandle) -> bool {
+    crate::get_setting(app, "weekdayOverrides")
+        .and_then(|v| v.as_object().map(|overrides| !overrides.is_empty()))
+        .unwrap_or(false)
+}
+
+/// Restarts the timer at every change of day while any weekday overrides are set.
+pub fn start_rollover_watch(app: &AppHandle) {
+    tauri::async_runtime::spawn({
+        let app = app.clone();
+        async move {
+            let mut today = SystemClock.local().date();
+            loop {
+                tokio::time::sleep(ROLLOVER_CHECK_PERIOD).await;
+                let now = SystemClock.local().date();
+                if now == today {
+                    continue;
+                }
+                today = now;
+                if has_overrides(&app) {
+                    log::info!("📆 New day, applying its weekday overrides");
+                    if let Err(problems) = crate::restart_timer(app.clone()) {
+                        log::error!("❌ Failed to restart the timer for the new day: {}", problems.join("; "));
+                    }
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn settings(value: Value) -> WeekdaySettings {
+        WeekdaySettings::from_settings(value.as_object().unwrap().clone())
+    }
+
+    #[test]
+    fn a_days_override_of_the_legacy_interval_beats_the_regular_micro_interval() {
+        let settings = settings(json!({
+            "microBreakIntervalMinutes": 20,
+            "weekdayOverrides": { "Fri": { "intervalMinutes": 40 } },
+        }));
+        let interval = |day| settings.first_of(&["microBreakIntervalMinutes", "intervalMinutes"], day);
+        assert_eq!(interval(Weekday::Fri), Some(&json!(40)));
+        assert_eq!(interval(Weekday::Thu), Some(&json!(20)));
+    }
+
+    #[test]
+    fn the_newer_key_wins_when_both_are_set_at_the_same_level() {
+        let settings = settings(json!({
+            "intervalMinutes": 25,
+            "microBreakIntervalMinutes": 20,
+            "weekdayOverrides": { "Friday": { "intervalMinutes": 40, "microBreakIntervalMinutes": 45 } },
+        }));
+        let interval = |day| settings.first_of(&["microBreakIntervalMinutes", "intervalMinutes"], day);
+        assert_eq!(interval(Weekday::Fri), Some(&json!(45)));
+        assert_eq!(interval(Weekday::Mon), Some(&json!(20)));
+    }
+
+    #[test]
+    fn only_overridable_keys_are_taken_from_a_day() {
+        let settings = settings(json!({
+            "strictMode": false,
+            "longBreakDurationSeconds": 300,
+            "weekdayOverrides": { "Sat": { "strictMode": true, "longBreakDurationSeconds": 600 } },
+        }));
+        assert_eq!(settings.get("strictMode", Weekday::Sat), Some(&json!(false)));
+        assert_eq!(settings.u64_on("longBreakDurationSeconds", Weekday::Sat, 0), 600);
+        assert_eq!(settings.u64_on("longBreakDurationSeconds", Weekday::Sun, 0), 300);
+    }
+}
@@ -0,0 +1,25 @@
+window.addEventListener('DOMContentLoaded', () => {
+    console.log('🚪 Exit confirmation window loaded');
+
+    const { invoke } = window.__TAURI__.core;
+    const currentWindow = window.__TAURI__.window.getCurrentWindow();
+
+    document.getElementById('exit-btn').addEventListener('click', async () => {
+        try {
+            await invoke('confirm_exit');
+        } catch (error) {
+            console.error('❌ Failed to exit:', error);
+        }
+    });
+
+    document.getElementById('cancel-btn').addEventListener('click', () => {
+        currentWindow.close();
+    });
+
+    // Escape keeps the app running, like the cancel button
+    document.addEventListener('keydown', (event) => {
+        if (event.key === 'Escape') {
+            currentWindow.close();
+        }
+    });
+});
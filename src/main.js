@@ -2,10 +2,17 @@
 
 window.addEventListener('DOMContentLoaded', async () => {
     console.log('🎬 Overlay window loaded');
+
+    // The break kind comes in the URL so the right content shows before the backend answers
+    const kind = new URLSearchParams(window.location.search).get('kind') ?? 'micro';
+    document.body.dataset.kind = kind;
     
     const countdownEl = document.getElementById('countdown');
     const progressEl = document.getElementById('progress');
     const skipBtn = document.getElementById('skip-btn');
+    const snoozeBtn = document.getElementById('snooze-btn');
+    const extendBtn = document.getElementById('extend-btn');
+    const errorEl = document.getElementById('overlay-error');
 
     // Verify all elements exist
     if (!countdownEl || !progressEl || !skipBtn) {
@@ -17,74 +24,242 @@ window.addEventListener('DOMContentLoaded', async () => {
         return;
     }
 
+    // Hidden until the config says how long to fade in for
+    document.body.style.opacity = 0;
+
     let duration = 20; // default
-    let timeLeft = duration;
-    let intervalId = null;
+    let strict = false;
+    let acknowledgeKey = 'Space';
+    let accessible = false;
+    let fadeInMs = 0;
 
-    // Get the duration from backend
+    // Get the duration from backend, then follow the backend's countdown
     async function init() {
         try {
             // Check if Tauri API is available
             if (!window.__TAURI__ || !window.__TAURI__.core) {
                 console.error('❌ Tauri API not available');
-                startCountdown(); // Start with default
                 return;
             }
 
             const invoke = window.__TAURI__.core.invoke;
-            console.log('📞 Calling get_overlay_duration...');
+            await window.__TAURI__.event.listen('break-tick', (event) => {
+                renderTick(event.payload.remainingSeconds, event.payload.durationSeconds);
+                renderGrace(event.payload.graceRemainingSeconds);
+            });
+            await window.__TAURI__.event.listen('break-confirmed', (event) => {
+                renderConfirmations(event.payload.confirmed);
+            });
+            // The backend closes the window once this fade has had time to play
+            await window.__TAURI__.event.listen('break-fading-out', (event) => {
+                fadeTo(0, event.payload);
+            });
+
+            const strings = await localize(invoke);
+
+            console.log('📞 Calling get_break_config...');
             
-            duration = await invoke('get_overlay_duration');
-            console.log('✅ Got duration:', duration);
+            const config = await invoke('get_break_config');
+            console.log('✅ Got break config:', config);
+            duration = config.durationSeconds;
+            skipBtn.hidden = !config.canSkip;
+            strict = config.strict;
+            snoozeBtn.hidden = strict;
+            acknowledgeKey = config.acknowledgeKey;
+            fadeInMs = config.fadeInMs;
+            document.documentElement.style.setProperty('--overlay-opacity', config.opacity);
+            document.body.dataset.theme = config.theme;
+            document.body.dataset.mode = config.mode;
+            document.body.dataset.blur = config.blur;
+            document.body.dataset.role = config.role;
+            document.getElementById('replay-badge').hidden = !config.replay;
+            showWorkedFor(config.workedSeconds, strings);
+            accessible = config.accessible;
+            document.body.dataset.accessible = accessible;
+            if (config.backgroundImage) {
+                document.documentElement.style.setProperty('--backdrop-image', `url('${config.backgroundImage}')`);
+            }
+            if (config.reminder) {
+                showReminder(config.reminder);
+            } else if (kind !== 'long') {
+                document.getElementById('break-text').textContent = await invoke('get_break_message', { breakType: kind });
+            } else {
+                document.getElementById('long-break-text').textContent = await invoke('get_break_message', { breakType: kind });
+                await showExercise(invoke);
+            }
             
-            timeLeft = duration;
-            countdownEl.textContent = timeLeft;
+            await showConfirmations(invoke);
+
+            countdownEl.textContent = duration;
             progressEl.style.width = '100%';
-            startCountdown();
+
+            // accessibilityMode: start on the first action so Tab and Enter reach the rest
+            if (accessible) {
+                document.querySelector('.confirmations button:not(:disabled), .actions button:not([hidden])')?.focus();
+            }
         } catch (error) {
             console.error('❌ Failed to get duration:', error);
-            // Fallback to default duration if invoke fails
-            startCountdown();
+        } finally {
+            fadeTo(1, fadeInMs);
         }
     }
 
-    function startCountdown() {
-        console.log('⏱️ Starting countdown:', timeLeft, 'seconds');
-        
-        intervalId = setInterval(() => {
-            timeLeft--;
-            countdownEl.textContent = timeLeft;
-            
-            const progress = (timeLeft / duration) * 100;
-            progressEl.style.width = progress + '%';
+    function fadeTo(opacity, ms) {
+        document.body.style.transition = `opacity ${ms}ms ease`;
+        document.body.style.opacity = opacity;
+    }
 
-            if (timeLeft <= 0) {
-                clearInterval(intervalId);
-                closeWindow();
-            }
-        }, 1000);
+    // Swap the built-in English text for the configured language (language setting)
+    async function localize(invoke) {
+        try {
+            const strings = await invoke('get_localized_strings');
+            document.querySelectorAll('[data-i18n]').forEach((el) => {
+                const text = strings[el.dataset.i18n];
+                if (text) el.textContent = text;
+            });
+            return strings;
+        } catch (error) {
+            console.error('❌ Failed to load localized strings:', error);
+            return {};
+        }
     }
 
-    function closeWindow() {
+    // "You've been working for 48 minutes." Left out for previews and stretches under a minute
+    function showWorkedFor(workedSeconds, strings) {
+        const minutes = Math.floor((workedSeconds ?? 0) / 60);
+        if (minutes < 1) return;
+        const el = document.getElementById('worked-for');
+        el.textContent = minutes === 1
+            ? strings['break.workedForMinute'] ?? "You've been working for a minute."
+            : (strings['break.workedFor'] ?? "You've been working for {minutes} minutes.").replace('{minutes}', minutes);
+        el.hidden = false;
+    }
+
+    // A reminder type (reminderTypes) replaces the eye-rest heading, text and icon
+    function showReminder(reminder) {
+        document.querySelector('h1.micro-only').textContent = reminder.name;
+        document.getElementById('break-text').textContent = reminder.message;
+        if (reminder.icon) {
+            document.getElementById('reminder-icon').textContent = reminder.icon;
+            document.getElementById('reminder-icon').hidden = false;
+            document.querySelector('.eye-icon').hidden = true;
+        }
+    }
+
+    // Suggest a stretch on long breaks; the generic text stays if none can be loaded
+    async function showExercise(invoke) {
         try {
-            if (!window.__TAURI__ || !window.__TAURI__.window) {
-                console.error('❌ Tauri window API not available for closing');
-                return;
-            }
-            
-            const getCurrentWindow = window.__TAURI__.window.getCurrentWindow;
-            const appWindow = getCurrentWindow();
-            console.log('🚪 Closing overlay window');
-            appWindow.close();
+            const exercise = await invoke('get_random_exercise');
+            document.getElementById('exercise-title').textContent = exercise.title;
+            document.getElementById('exercise-text').textContent = exercise.description;
+            document.getElementById('exercise').hidden = false;
         } catch (error) {
-            console.error('❌ Failed to close window:', error);
+            console.error('❌ Failed to load an exercise:', error);
         }
     }
 
-    skipBtn.addEventListener('click', () => {
+    // Prompts to confirm, in order, before the acknowledge key ends the break
+    async function showConfirmations(invoke) {
+        const prompts = await invoke('get_confirmation_prompts');
+        if (prompts.length === 0) return;
+
+        const list = document.getElementById('confirmations');
+        prompts.forEach((prompt, index) => {
+            const button = document.createElement('button');
+            button.textContent = prompt;
+            button.addEventListener('click', async () => {
+                try {
+                    await invoke('confirm_break_prompt', { index });
+                } catch (error) {
+                    console.error('❌ Failed to confirm:', error);
+                    errorEl.textContent = error;
+                }
+            });
+            const item = document.createElement('li');
+            item.appendChild(button);
+            list.appendChild(item);
+        });
+        list.hidden = false;
+        renderConfirmations(0);
+    }
+
+    // Only the next prompt can be clicked; confirmed ones stay ticked
+    function renderConfirmations(confirmed) {
+        document.querySelectorAll('#confirmations button').forEach((button, index) => {
+            button.classList.toggle('confirmed', index < confirmed);
+            button.disabled = index !== confirmed;
+        });
+    }
+
+    // The backend owns the countdown and closes the window when it reaches zero.
+    function renderTick(remainingSeconds, durationSeconds) {
+        duration = durationSeconds;
+        countdownEl.textContent = remainingSeconds;
+        progressEl.style.width = (remainingSeconds / duration) * 100 + '%';
+    }
+
+    // The backend refuses to end the break before minimumBreakSeconds are up
+    function renderGrace(graceRemainingSeconds) {
+        const locked = graceRemainingSeconds > 0;
+        skipBtn.disabled = locked;
+        skipBtn.title = locked ? `Available in ${graceRemainingSeconds}s` : '';
+    }
+
+    skipBtn.addEventListener('click', async () => {
         console.log('⏭️ Skip button clicked');
-        if (intervalId) clearInterval(intervalId);
-        closeWindow();
+        try {
+            await window.__TAURI__.core.invoke('skip_break');
+        } catch (error) {
+            console.error('❌ Failed to skip:', error);
+            errorEl.textContent = error;
+        }
+    });
+
+    extendBtn.addEventListener('click', async () => {
+        console.log('➕ Extend button clicked');
+        try {
+            // The new remaining time arrives as a break-tick
+            await window.__TAURI__.core.invoke('extend_break', { seconds: 60 });
+        } catch (error) {
+            console.error('❌ Failed to extend:', error);
+            errorEl.textContent = error;
+        }
+    });
+
+    snoozeBtn.addEventListener('click', async () => {
+        console.log('😴 Snooze button clicked');
+        try {
+            await window.__TAURI__.core.invoke('snooze_break', { minutes: 5 });
+        } catch (error) {
+            console.error('❌ Failed to snooze:', error);
+            errorEl.textContent = error;
+        }
+    });
+
+    // The acknowledge key ends the break; the backend decides whether that's allowed yet
+    document.addEventListener('keydown', async (event) => {
+        if (event.code !== acknowledgeKey || event.repeat) return;
+        // accessibilityMode: Space and Enter on a focused button press that button instead
+        if (accessible && event.target instanceof HTMLButtonElement) return;
+        event.preventDefault();
+        console.log('⌨️ Acknowledge key pressed');
+        try {
+            await window.__TAURI__.core.invoke('acknowledge_break');
+        } catch (error) {
+            console.error('❌ Failed to acknowledge:', error);
+            errorEl.textContent = error;
+        }
+    });
+
+    document.addEventListener('keydown', async (event) => {
+        if (event.key !== 'Escape' || strict) return;
+        console.log('⎋ Escape pressed, dismissing break');
+        try {
+            await window.__TAURI__.core.invoke('dismiss_overlay');
+        } catch (error) {
+            console.error('❌ Failed to dismiss:', error);
+            errorEl.textContent = error;
+        }
     });
 
     // Initialize on load
@@ -12,13 +12,16 @@ window.addEventListener('DOMContentLoaded', async () => {
     // Destructure necessary functions from the Tauri API
     const { invoke } = window.__TAURI__.core;
     const { Store } = window.__TAURI__.store;
-    const { enable, disable } = window.__TAURI__.autostart;
 
     // Get DOM elements
     const intervalInput = document.getElementById('interval');
     const durationInput = document.getElementById('duration');
     const autostartInput = document.getElementById('autostart');
+    const breakEndCommandInput = document.getElementById('break-end-command');
+    const allowShellHooksInput = document.getElementById('allow-shell-hooks');
     const saveButton = document.getElementById('save-btn');
+    const previewButton = document.getElementById('preview-btn');
+    const resetButton = document.getElementById('reset-btn');
     const statusMessage = document.getElementById('status-message');
 
     let settingsStore = null;
@@ -49,64 +52,124 @@ window.addEventListener('DOMContentLoaded', async () => {
             const interval = await settingsStore.get('intervalMinutes') ?? 20;
             const duration = await settingsStore.get('overlayDurationSeconds') ?? 20;
             const autoStart = await settingsStore.get('autoStart') ?? false;
+            const breakEndCommand = await settingsStore.get('onBreakEndCommand') ?? '';
+            const allowShellHooks = await settingsStore.get('allowShellHooks') ?? false;
 
             console.log('✅ Settings loaded:', { interval, duration, autoStart });
             intervalInput.value = interval;
             durationInput.value = duration;
             autostartInput.checked = autoStart;
+            breakEndCommandInput.value = breakEndCommand;
+            allowShellHooksInput.checked = allowShellHooks;
+            await syncAutostartStatus(autoStart);
+            await showBreaksPerDay();
         } catch (error) {
             console.error('❌ Failed to load settings:', error);
             showStatus('Error loading settings: ' + error.message, 'error');
         }
     }
 
+    // Rough breaks per workday for the interval being edited, under the stored work hours
+    async function showBreaksPerDay() {
+        const estimateEl = document.getElementById('breaks-per-day');
+        try {
+            const estimate = await invoke('breaks_per_day_estimate', {
+                intervalMinutes: parseInt(intervalInput.value, 10) || 0,
+                workStart: await settingsStore.get('workStartTime') ?? '',
+                workEnd: await settingsStore.get('workEndTime') ?? '',
+            });
+            estimateEl.textContent = `(≈ ${Math.round(estimate)} breaks per workday)`;
+        } catch (error) {
+            estimateEl.textContent = '';
+            console.error('❌ Failed to estimate breaks per day:', error);
+        }
+    }
+
+    // Show whether the OS really launches the app at login; saving applies the toggle again
+    async function syncAutostartStatus(stored) {
+        try {
+            const registered = await invoke('get_autostart_status');
+            if (registered !== stored) {
+                console.warn('⚠️ Autostart registration differs from the setting:', { registered, stored });
+                autostartInput.checked = registered;
+                showStatus(`Autostart is ${registered ? 'on' : 'off'} in the system. Save to keep it that way.`, 'error');
+            }
+        } catch (error) {
+            console.error('❌ Failed to read autostart status:', error);
+        }
+    }
+
     // Save the current settings from the input fields to the store
     async function saveSettings() {
         console.log('💾 Saving settings...');
-        if (!settingsStore) {
-            const initialized = await initStore();
-            if (!initialized) return;
-        }
 
         try {
             const interval = parseInt(intervalInput.value, 10);
             const duration = parseInt(durationInput.value, 10);
 
+            clearInvalidFields();
+
             // Validate input values
-            if (isNaN(interval) || interval < 1 || interval > 180) {
-                showStatus('Interval must be between 1-180 minutes', 'error');
+            if (isNaN(interval) || interval < 1 || interval > 480) {
+                intervalInput.classList.add('invalid');
+                showStatus('Interval must be between 1-480 minutes', 'error');
                 return;
             }
-            if (isNaN(duration) || duration < 5 || duration > 300) {
-                showStatus('Duration must be between 5-300 seconds', 'error');
+            if (isNaN(duration) || duration < 5 || duration > 600) {
+                durationInput.classList.add('invalid');
+                showStatus('Duration must be between 5-600 seconds', 'error');
                 return;
             }
 
-            // Set values in the store
-            await settingsStore.set('intervalMinutes', interval);
-            await settingsStore.set('overlayDurationSeconds', duration);
-            await settingsStore.set('autoStart', autostartInput.checked);
-            await settingsStore.save();
-            console.log('✅ Settings saved to store');
+            // The backend validates and stores these; show the values it settled on
+            try {
+                durationInput.value = await invoke('set_duration', { seconds: duration });
+            } catch (error) {
+                console.error('❌ Backend rejected duration:', error);
+                highlightInvalidFields([error]);
+                showStatus(error, 'error');
+                return;
+            }
+            console.log('✅ Duration saved');
 
-            // Enable or disable autostart based on the checkbox
+            // The backend registers autostart with the OS and only stores it if that worked
+            let autostartError = null;
             try {
-                if (autostartInput.checked) {
-                    await enable();
-                    console.log('✅ Autostart enabled');
-                } else {
-                    await disable();
-                    console.log('✅ Autostart disabled');
-                }
+                await invoke('set_autostart', { enabled: autostartInput.checked });
+                console.log('✅ Autostart', autostartInput.checked ? 'enabled' : 'disabled');
             } catch (error) {
                 console.warn('⚠️ Autostart setting failed:', error);
+                autostartInput.checked = !autostartInput.checked;
+                autostartError = error;
             }
 
-            // Restart the timer in the backend to apply new settings
-            console.log('🔄 Restarting timer...');
-            await invoke('restart_timer');
+            try {
+                await invoke('set_shell_hook', {
+                    command: breakEndCommandInput.value,
+                    enabled: allowShellHooksInput.checked,
+                });
+            } catch (error) {
+                console.error('❌ Failed to save the shell hook:', error);
+                showStatus('Error saving the break command: ' + error, 'error');
+                return;
+            }
+
+            // Setting the interval restarts the timer in the backend
+            console.log('🔄 Saving interval and restarting timer...');
+            try {
+                intervalInput.value = await invoke('set_interval', { minutes: interval });
+            } catch (error) {
+                console.error('❌ Backend rejected settings:', error);
+                highlightInvalidFields([error]);
+                showStatus(error, 'error');
+                return;
+            }
             console.log('✅ Timer restarted');
 
+            if (autostartError) {
+                showStatus('Could not change autostart: ' + autostartError, 'error');
+                return;
+            }
             showStatus('Settings saved successfully!', 'success');
         } catch (error) {
             console.error('❌ Failed to save settings:', error);
@@ -114,6 +177,118 @@ window.addEventListener('DOMContentLoaded', async () => {
         }
     }
 
+    // Restore every setting to its default in the backend, then show the new values
+    async function resetSettings() {
+        if (!confirm('Restore all settings to their defaults?')) return;
+        clearInvalidFields();
+        try {
+            await invoke('reset_settings');
+            await loadSettings();
+            showStatus('Settings restored to defaults', 'success');
+        } catch (error) {
+            console.error('❌ Failed to reset settings:', error);
+            showStatus('Error resetting settings: ' + error, 'error');
+        }
+    }
+
+    // Show the overlay for a few seconds; nothing is recorded and the schedule is untouched
+    async function previewBreak() {
+        try {
+            if (await invoke('is_overlay_visible')) {
+                showStatus('A break is already showing', 'error');
+                return;
+            }
+            await invoke('preview_overlay');
+        } catch (error) {
+            console.error('❌ Failed to preview the break:', error);
+            showStatus('Error previewing break: ' + error, 'error');
+        }
+    }
+
+    // Fetch break statistics from the backend and render them
+    async function loadStats() {
+        try {
+            const stats = await invoke('get_stats');
+            const format = (counts) => {
+                const parts = [`${counts.completed} taken`, `${counts.skipped} skipped`];
+                if (counts.snoozed) parts.push(`${counts.snoozed} snoozed`);
+                if (counts.autoPostponed) parts.push(`${counts.autoPostponed} held back`);
+                if (counts.missed) parts.push(`${counts.missed} missed`);
+                return parts.join(' · ');
+            };
+            document.getElementById('stats-today').textContent = format(stats.today);
+            document.getElementById('stats-week').textContent = format(stats.thisWeek);
+            document.getElementById('stats-all-time').textContent = format(stats.allTime);
+            document.getElementById('stats-summary').textContent =
+                `${stats.totalBreakMinutes} minutes spent on breaks in total`;
+            const streak = await invoke('get_streak');
+            document.getElementById('stats-streak').textContent =
+                `🔥 ${streak.current}-day streak (best: ${streak.longest})`;
+        } catch (error) {
+            console.error('❌ Failed to load stats:', error);
+        }
+    }
+
+    // Show the diagnostic report and put it on the clipboard for a support request
+    async function copyDiagnostics() {
+        try {
+            const report = JSON.stringify(await invoke('run_diagnostics'), null, 2);
+            const reportEl = document.getElementById('diagnostics-report');
+            reportEl.textContent = report;
+            reportEl.hidden = false;
+            await navigator.clipboard.writeText(report);
+            showStatus('Diagnostics copied to the clipboard', 'success');
+        } catch (error) {
+            console.error('❌ Failed to collect diagnostics:', error);
+            showStatus('Error collecting diagnostics: ' + error, 'error');
+        }
+    }
+
+    // Map backend validation problems (which quote the setting key) back to their inputs
+    const fieldsByKey = {
+        intervalMinutes: intervalInput,
+        overlayDurationSeconds: durationInput,
+        autoStart: autostartInput,
+    };
+
+    function highlightInvalidFields(problems) {
+        for (const [key, input] of Object.entries(fieldsByKey)) {
+            if (problems.some((problem) => problem.includes(`'${key}'`))) {
+                input.classList.add('invalid');
+            }
+        }
+    }
+
+    function clearInvalidFields() {
+        Object.values(fieldsByKey).forEach((input) => input.classList.remove('invalid'));
+    }
+
+    // Without a tray icon this window is the only place to pause or quit the app
+    async function showAppControls() {
+        if (await invoke('is_tray_available')) {
+            return;
+        }
+        const controls = document.getElementById('app-controls');
+        const pauseButton = document.getElementById('pause-btn');
+        const refreshPauseButton = async () => {
+            const status = await invoke('get_timer_status');
+            pauseButton.textContent = status.paused ? 'Resume Nudges' : 'Pause Nudges';
+        };
+        pauseButton.addEventListener('click', async () => {
+            try {
+                const status = await invoke('get_timer_status');
+                await invoke(status.paused ? 'resume_timer' : 'pause_timer');
+                await refreshPauseButton();
+            } catch (error) {
+                console.error('❌ Failed to pause or resume:', error);
+                showStatus('Error: ' + error, 'error');
+            }
+        });
+        document.getElementById('quit-btn').addEventListener('click', () => invoke('exit_app'));
+        await refreshPauseButton();
+        controls.hidden = false;
+    }
+
     // Display a status message to the user for 3 seconds
     function showStatus(message, type) {
         statusMessage.textContent = message;
@@ -130,7 +305,22 @@ window.addEventListener('DOMContentLoaded', async () => {
 
     // Add a click event listener to the save button
     saveButton.addEventListener('click', saveSettings);
+    intervalInput.addEventListener('input', showBreaksPerDay);
+    allowShellHooksInput.addEventListener('change', () => {
+        if (allowShellHooksInput.checked &&
+            !confirm('The command will run with your full permissions after every break. Allow it?')) {
+            allowShellHooksInput.checked = false;
+        }
+    });
+    previewButton.addEventListener('click', previewBreak);
+    resetButton.addEventListener('click', resetSettings);
+    document.getElementById('diagnostics-btn').addEventListener('click', copyDiagnostics);
+
+    // Keep the stats current while the window is open
+    await window.__TAURI__.event.listen('break-finished', loadStats);
 
     // Load settings when the page is ready
     await loadSettings();
+    await loadStats();
+    await showAppControls();
 });
\ No newline at end of file